@@ -0,0 +1,275 @@
+use crate::{files::write_ron, GameState};
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf};
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(read_bindings())
+            .insert_resource(Actions::default())
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_actions_system.after(InputSystem),
+            );
+    }
+}
+
+const CONFIG_DIRECTORY: &str = "puzzle-up";
+const BINDINGS_FILE: &str = "input.ron";
+const STICK_THRESHOLD: f32 = 0.5;
+/// The actions pressed by tilting the left stick of a gamepad along an axis and direction.
+const STICK_ACTIONS: [(GamepadAxisType, f32, Action); 6] = [
+    (GamepadAxisType::LeftStickX, -1.0, Action::MoveLeft),
+    (GamepadAxisType::LeftStickX, 1.0, Action::MoveRight),
+    (GamepadAxisType::LeftStickX, -1.0, Action::PreviousSlot),
+    (GamepadAxisType::LeftStickX, 1.0, Action::NextSlot),
+    (GamepadAxisType::LeftStickY, 1.0, Action::NextCharacter),
+    (GamepadAxisType::LeftStickY, -1.0, Action::PreviousCharacter),
+];
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Jump,
+    MoveLeft,
+    MoveRight,
+    Interact,
+    Confirm,
+    Cancel,
+    DeleteDigit,
+    RevealHint,
+    NextCharacter,
+    PreviousCharacter,
+    NextSlot,
+    PreviousSlot,
+    OpenJournal,
+}
+
+impl Action {
+    pub const ALL: [Action; 13] = [
+        Self::Jump,
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Interact,
+        Self::Confirm,
+        Self::Cancel,
+        Self::DeleteDigit,
+        Self::RevealHint,
+        Self::NextCharacter,
+        Self::PreviousCharacter,
+        Self::NextSlot,
+        Self::PreviousSlot,
+        Self::OpenJournal,
+    ];
+}
+
+/// The keys and gamepad buttons that trigger an action.
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct Binding {
+    #[serde(default)]
+    pub keys: Vec<KeyCode>,
+    #[serde(default)]
+    pub buttons: Vec<GamepadButtonType>,
+}
+
+impl Binding {
+    fn new(keys: &[KeyCode], buttons: &[GamepadButtonType]) -> Self {
+        Self {
+            keys: keys.to_vec(),
+            buttons: buttons.to_vec(),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        let inputs = Vec::from_iter(
+            self.keys
+                .iter()
+                .map(|key| format!("{:?}", key))
+                .chain(self.buttons.iter().map(|button| format!("{:?}", button))),
+        );
+        if inputs.is_empty() {
+            "unbound".to_string()
+        } else {
+            inputs.join(", ")
+        }
+    }
+}
+
+#[derive(Default, Deref, DerefMut, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct InputBindings(HashMap<Action, Binding>);
+
+impl InputBindings {
+    /// Lists the inputs bound to an action, for hints on screen.
+    pub fn description(&self, action: Action) -> String {
+        self.get(&action)
+            .map(Binding::description)
+            .unwrap_or_default()
+    }
+}
+
+pub fn default_bindings() -> InputBindings {
+    use GamepadButtonType::*;
+    InputBindings(HashMap::from_iter([
+        (
+            Action::Jump,
+            Binding::new(&[KeyCode::Up, KeyCode::W], &[South]),
+        ),
+        (
+            Action::MoveLeft,
+            Binding::new(&[KeyCode::Left, KeyCode::A], &[DPadLeft]),
+        ),
+        (
+            Action::MoveRight,
+            Binding::new(&[KeyCode::Right, KeyCode::D], &[DPadRight]),
+        ),
+        (Action::Interact, Binding::new(&[KeyCode::Space], &[West])),
+        (
+            Action::Confirm,
+            Binding::new(&[KeyCode::Return, KeyCode::NumpadEnter], &[South]),
+        ),
+        (
+            Action::Cancel,
+            Binding::new(&[KeyCode::Escape], &[East, Start]),
+        ),
+        (
+            Action::DeleteDigit,
+            Binding::new(&[KeyCode::Back], &[North]),
+        ),
+        (
+            Action::RevealHint,
+            Binding::new(&[KeyCode::Tab], &[LeftTrigger]),
+        ),
+        (
+            Action::NextCharacter,
+            Binding::new(&[KeyCode::Up], &[DPadUp]),
+        ),
+        (
+            Action::PreviousCharacter,
+            Binding::new(&[KeyCode::Down], &[DPadDown]),
+        ),
+        (
+            Action::NextSlot,
+            Binding::new(&[KeyCode::Right], &[DPadRight]),
+        ),
+        (
+            Action::PreviousSlot,
+            Binding::new(&[KeyCode::Left], &[DPadLeft]),
+        ),
+        (Action::OpenJournal, Binding::new(&[KeyCode::J], &[Select])),
+    ]))
+}
+
+fn bindings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|directory| directory.join(CONFIG_DIRECTORY).join(BINDINGS_FILE))
+}
+
+/// Reads the user's bindings, falling back to the defaults for actions the file does not mention.
+pub fn read_bindings() -> InputBindings {
+    let mut bindings = default_bindings();
+    let path = match bindings_path() {
+        Some(path) => path,
+        None => return bindings,
+    };
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => return bindings,
+    };
+    match ron::from_str::<InputBindings>(&contents) {
+        Ok(saved) => bindings.extend(saved.0),
+        Err(error) => warn!("Ignoring malformed input config {:?}: {}", path, error),
+    }
+    bindings
+}
+
+pub fn write_bindings(bindings: &InputBindings) {
+    let path = match bindings_path() {
+        Some(path) => path,
+        None => {
+            warn!("No config directory is available, input bindings will not be saved!");
+            return;
+        }
+    };
+    if let Err(error) = write_ron(&path, bindings) {
+        warn!("Could not save input bindings to {:?}: {}", path, error);
+    }
+}
+
+/// The actions triggered this frame by the keyboard and any connected gamepad.
+#[derive(Default)]
+pub struct Actions {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+}
+
+impl Actions {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// Keeps the action from being handled again this frame, e.g. by the state it switched to.
+    pub fn consume(&mut self, action: Action) {
+        self.just_pressed.remove(&action);
+    }
+
+    /// Consumes the action and switches to the screen it opens, if it was pressed this frame.
+    /// The press is consumed even when another press opened a screen in the same frame first,
+    /// in which case that screen stays; otherwise the press would close the screen it opened.
+    /// Returns whether the state was switched.
+    pub fn open_screen(
+        &mut self,
+        action: Action,
+        state: &mut State<GameState>,
+        screen: GameState,
+    ) -> bool {
+        if !self.just_pressed(action) {
+            return false;
+        }
+        self.consume(action);
+        state.set(screen).is_ok()
+    }
+}
+
+fn update_actions_system(
+    bindings: Res<InputBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+    mut actions: ResMut<Actions>,
+) {
+    let previous = std::mem::take(&mut actions.pressed);
+    for (action, binding) in bindings.iter() {
+        let buttons = gamepads.iter().flat_map(|gamepad| {
+            binding
+                .buttons
+                .iter()
+                .map(move |button| GamepadButton::new(*gamepad, *button))
+        });
+        if keyboard_input.any_pressed(binding.keys.iter().copied())
+            || gamepad_input.any_pressed(buttons)
+        {
+            actions.pressed.insert(*action);
+        }
+    }
+    for gamepad in gamepads.iter() {
+        for (axis, direction, action) in STICK_ACTIONS {
+            let tilt = gamepad_axes
+                .get(GamepadAxis::new(*gamepad, axis))
+                .unwrap_or_default();
+            if tilt * direction >= STICK_THRESHOLD {
+                actions.pressed.insert(action);
+            }
+        }
+    }
+    actions.just_pressed = actions.pressed.difference(&previous).copied().collect();
+}
@@ -0,0 +1,246 @@
+use crate::GameState;
+use bevy::{prelude::*, utils::HashMap};
+use serde::Deserialize;
+
+pub struct SpriteAnimationPlugin;
+
+impl Plugin for SpriteAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<AnimationEvent>()
+            .add_system(animate_sprites_system.label(AnimateSprites));
+    }
+}
+
+/// Labels the system that animates sprites, so the systems writing `MotionState` can run first.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AnimateSprites;
+
+const MOVING_SPEED: f32 = 1.0;
+
+#[derive(Default, Clone, Copy, Eq, PartialEq, Deserialize)]
+pub enum PlayMode {
+    #[default]
+    Loop,
+    Once,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Clip {
+    pub frames: Vec<usize>,
+    pub frame_duration: f32,
+    #[serde(default)]
+    pub mode: PlayMode,
+    /// Events sent when the frame at the given position of the clip is shown, e.g. footsteps.
+    #[serde(default)]
+    pub events: Vec<(usize, String)>,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Deserialize)]
+pub enum Condition {
+    Grounded,
+    Airborne,
+    Moving,
+    Still,
+    Rising,
+    Falling,
+    /// The current clip is a one-shot clip that has shown its last frame.
+    Finished,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct Transition {
+    /// The clip the transition leaves, or any other clip when missing.
+    #[serde(default)]
+    pub from: Option<String>,
+    pub to: String,
+    pub when: Vec<Condition>,
+}
+
+/// The clips of a sprite sheet and the rules for switching between them.
+#[derive(Clone, Deserialize)]
+pub struct AnimationGraph {
+    pub initial: String,
+    pub clips: HashMap<String, Clip>,
+    #[serde(default)]
+    pub transitions: Vec<Transition>,
+}
+
+impl AnimationGraph {
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.clips.contains_key(&self.initial) {
+            return Err(format!("the initial clip {} does not exist", self.initial));
+        }
+        for (name, clip) in self.clips.iter() {
+            if clip.frames.is_empty() {
+                return Err(format!("clip {} has no frames", name));
+            }
+            if clip.frame_duration <= 0.0 {
+                return Err(format!("clip {} has a non-positive frame duration", name));
+            }
+        }
+        for transition in self.transitions.iter() {
+            for clip in transition.from.iter().chain([&transition.to]) {
+                if !self.clips.contains_key(clip) {
+                    return Err(format!("a transition uses the missing clip {}", clip));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The physics state the transitions of an animation graph are checked against, kept up to
+/// date by whoever owns the animated body.
+#[derive(Default, Clone, Copy, Component)]
+pub struct MotionState {
+    pub grounded: bool,
+    pub velocity: Vec2,
+}
+
+impl MotionState {
+    fn satisfies(&self, condition: Condition, finished: bool) -> bool {
+        match condition {
+            Condition::Grounded => self.grounded,
+            Condition::Airborne => !self.grounded,
+            Condition::Moving => self.velocity.x.abs() >= MOVING_SPEED,
+            Condition::Still => self.velocity.x.abs() < MOVING_SPEED,
+            Condition::Rising => self.velocity.y > 0.0,
+            Condition::Falling => self.velocity.y <= 0.0,
+            Condition::Finished => finished,
+        }
+    }
+}
+
+/// Sent when an animation shows a frame that has an event attached to it.
+pub struct AnimationEvent {
+    pub entity: Entity,
+    pub name: String,
+}
+
+#[derive(Component)]
+pub struct SpriteAnimator {
+    graph: AnimationGraph,
+    current: String,
+    position: usize,
+    shown: Option<usize>,
+    finished: bool,
+    timer: Timer,
+}
+
+impl SpriteAnimator {
+    pub fn new(graph: AnimationGraph) -> Self {
+        let frame_duration = graph
+            .clips
+            .get(&graph.initial)
+            .expect("The initial clip should exist in a validated graph!")
+            .frame_duration;
+        Self {
+            current: graph.initial.clone(),
+            graph,
+            position: 0,
+            shown: None,
+            finished: false,
+            timer: Timer::from_seconds(frame_duration, true),
+        }
+    }
+
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Starts the graph's initial clip over, as if the animator had just been created.
+    pub fn reset(&mut self) {
+        let initial = self.graph.initial.clone();
+        self.current.clear();
+        self.play(&initial);
+    }
+
+    /// Switches to another clip, starting it from its first frame.
+    pub fn play(&mut self, clip: &str) {
+        if clip == self.current {
+            return;
+        }
+        if let Some(frame_duration) = self.graph.clips.get(clip).map(|clip| clip.frame_duration) {
+            self.current = clip.to_string();
+            self.position = 0;
+            self.shown = None;
+            self.finished = false;
+            self.timer = Timer::from_seconds(frame_duration, true);
+        }
+    }
+
+    fn clip(&self) -> &Clip {
+        self.graph
+            .clips
+            .get(&self.current)
+            .expect("The current clip is always one of the graph's clips!")
+    }
+
+    fn next_clip(&self, motion: &MotionState) -> Option<String> {
+        self.graph
+            .transitions
+            .iter()
+            .filter(|transition| match &transition.from {
+                Some(from) => *from == self.current,
+                None => transition.to != self.current,
+            })
+            .find(|transition| {
+                transition
+                    .when
+                    .iter()
+                    .all(|condition| motion.satisfies(*condition, self.finished))
+            })
+            .map(|transition| transition.to.clone())
+    }
+}
+
+fn animate_sprites_system(
+    state: Res<State<GameState>>,
+    time: Res<Time>,
+    mut events: EventWriter<AnimationEvent>,
+    mut sprites: Query<(
+        Entity,
+        &mut SpriteAnimator,
+        &mut TextureAtlasSprite,
+        Option<&MotionState>,
+    )>,
+) {
+    if !state.current().is_playing() {
+        return;
+    }
+    for (entity, mut animator, mut sprite, motion) in sprites.iter_mut() {
+        if let Some(clip) = animator.next_clip(&motion.copied().unwrap_or_default()) {
+            animator.play(&clip);
+        }
+
+        if animator.timer.tick(time.delta()).just_finished() && !animator.finished {
+            let length = animator.clip().frames.len();
+            if animator.position + 1 < length {
+                animator.position += 1;
+            } else if animator.clip().mode == PlayMode::Loop {
+                animator.position = 0;
+                animator.shown = None;
+            } else {
+                animator.finished = true;
+            }
+        }
+
+        if animator.shown == Some(animator.position) {
+            continue;
+        }
+        let position = animator.position;
+        animator.shown = Some(position);
+        let clip = animator.clip();
+        sprite.index = clip.frames[position];
+        for (_, name) in clip.events.iter().filter(|(frame, _)| *frame == position) {
+            events.send(AnimationEvent {
+                entity,
+                name: name.clone(),
+            });
+        }
+    }
+}
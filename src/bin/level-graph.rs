@@ -0,0 +1,59 @@
+use puzzle_up::{level_graph::LevelGraph, validation::load_project};
+use std::{env, process::ExitCode};
+
+const MAP_PATH: &str = "assets/map.ldtk";
+
+fn main() -> ExitCode {
+    let arguments = Vec::from_iter(env::args().skip(1));
+    let dot = arguments.iter().any(|argument| argument == "--dot");
+    let path = arguments
+        .iter()
+        .find(|argument| !argument.starts_with("--"))
+        .cloned()
+        .unwrap_or_else(|| MAP_PATH.to_string());
+    let project = match load_project(&path) {
+        Ok(project) => project,
+        Err(error) => {
+            eprintln!("Could not load {}: {}", path, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let graph = LevelGraph::new(&project);
+    if dot {
+        print!("{}", graph.to_dot());
+        return ExitCode::SUCCESS;
+    }
+
+    for level in graph.unreachable_levels() {
+        println!("Unreachable level: {}", level.identifier);
+    }
+    for level in graph.dead_ends() {
+        println!("Level the end can't be reached from: {}", level.identifier);
+    }
+    let path = match graph.win_level() {
+        Some(win_level) => graph.shortest_path(&win_level.iid),
+        None => {
+            println!("No level shows the winning text.");
+            return ExitCode::FAILURE;
+        }
+    };
+    match path {
+        Some(path) => {
+            println!("Shortest path to the end ({} riddles):", path.len());
+            for edge in path {
+                println!(
+                    "  {} -> {}: {}",
+                    graph.name(&edge.from),
+                    graph.name(&edge.to),
+                    edge.question.replace('\n', " ")
+                );
+            }
+            ExitCode::SUCCESS
+        }
+        None => {
+            println!("The winning level cannot be reached from the starting level.");
+            ExitCode::FAILURE
+        }
+    }
+}
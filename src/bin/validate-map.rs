@@ -0,0 +1,27 @@
+use puzzle_up::validation::{load_project, validate_project};
+use std::{env, process::ExitCode};
+
+const MAP_PATH: &str = "assets/map.ldtk";
+
+fn main() -> ExitCode {
+    let path = env::args().nth(1).unwrap_or_else(|| MAP_PATH.to_string());
+    let project = match load_project(&path) {
+        Ok(project) => project,
+        Err(error) => {
+            eprintln!("Could not load {}: {}", path, error);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let issues = validate_project(&project);
+    for issue in issues.iter() {
+        println!("{}", issue);
+    }
+    if issues.is_empty() {
+        println!("{} levels checked, no issues found.", project.levels.len());
+        ExitCode::SUCCESS
+    } else {
+        println!("{} issues found.", issues.len());
+        ExitCode::FAILURE
+    }
+}
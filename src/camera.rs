@@ -0,0 +1,196 @@
+use crate::{player::Player, progress::RestoreSpawn, riddles::DoorOpened, GameState};
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DoorFocus>()
+            .add_startup_system(camera_setup_system)
+            .add_system_set(
+                SystemSet::on_exit(GameState::LevelLoading)
+                    .with_system(reset_camera_system.after(RestoreSpawn)),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::MapExploring).with_system(follow_player_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::DoorOpening).with_system(focus_door_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::DoorOpening).with_system(clear_door_focus_system),
+            );
+    }
+}
+
+const DEAD_ZONE: Vec2 = Vec2::new(64.0, 48.0);
+const SMOOTHING: f32 = 5.0;
+
+/// The door the camera shows while it opens.
+#[derive(Default, Deref, DerefMut)]
+struct DoorFocus(Option<Vec2>);
+
+#[derive(Component)]
+pub struct MainCamera;
+
+fn camera_setup_system(mut commands: Commands) {
+    commands
+        .spawn_bundle(Camera2dBundle::default())
+        .insert(MainCamera);
+}
+
+/// Starts every level with the camera on the player, so it doesn't pan across the level to them.
+fn reset_camera_system(
+    windows: Res<Windows>,
+    levels: Res<Assets<LdtkLevel>>,
+    level_info: Query<&Handle<LdtkLevel>>,
+    player_info: Query<&Transform, (With<Player>, Without<MainCamera>)>,
+    mut camera_info: Query<(&mut Transform, &OrthographicProjection), With<MainCamera>>,
+) {
+    let (mut camera, projection) = camera_info.single_mut();
+    let level = level_info
+        .get_single()
+        .ok()
+        .and_then(|handle| levels.get(handle));
+    let position = match (level, windows.get_primary(), player_info.get_single()) {
+        (Some(level), Some(window), Ok(player)) => {
+            // The level is centered on the origin, while the player is placed from its corner.
+            let half_level = Vec2::new(level.level.px_wid as f32, level.level.px_hei as f32) / 2.0;
+            clamp_to_level(
+                player.translation.truncate() - half_level,
+                projection,
+                window,
+                level,
+            )
+        }
+        _ => Vec2::ZERO,
+    };
+    camera.translation.x = position.x;
+    camera.translation.y = position.y;
+}
+
+fn clamp_axis(position: f32, half_view: f32, half_level: f32) -> f32 {
+    if half_view >= half_level {
+        0.0
+    } else {
+        position.clamp(half_view - half_level, half_level - half_view)
+    }
+}
+
+/// Moves a camera position so the view doesn't show anything outside the level.
+fn clamp_to_level(
+    position: Vec2,
+    projection: &OrthographicProjection,
+    window: &Window,
+    level: &LdtkLevel,
+) -> Vec2 {
+    let half_view = Vec2::new(window.width(), window.height()) * projection.scale / 2.0;
+    let half_level = Vec2::new(level.level.px_wid as f32, level.level.px_hei as f32) / 2.0;
+    Vec2::new(
+        clamp_axis(position.x, half_view.x, half_level.x),
+        clamp_axis(position.y, half_view.y, half_level.y),
+    )
+}
+
+/// Moves the camera smoothly towards `target` without showing anything outside the level.
+fn move_camera(
+    camera: &mut Transform,
+    projection: &OrthographicProjection,
+    target: Vec2,
+    delta_seconds: f32,
+    window: &Window,
+    level: &LdtkLevel,
+) {
+    let position = camera
+        .translation
+        .truncate()
+        .lerp(target, 1.0 - (-SMOOTHING * delta_seconds).exp());
+    let position = clamp_to_level(position, projection, window, level);
+    camera.translation.x = position.x;
+    camera.translation.y = position.y;
+}
+
+fn follow_player_system(
+    time: Res<Time>,
+    windows: Res<Windows>,
+    levels: Res<Assets<LdtkLevel>>,
+    level_info: Query<&Handle<LdtkLevel>>,
+    player_info: Query<&GlobalTransform, With<Player>>,
+    mut camera_info: Query<(&mut Transform, &OrthographicProjection), With<MainCamera>>,
+) {
+    let (mut camera, projection) = camera_info.single_mut();
+    let player = player_info.single().translation().truncate();
+    let level = match level_info
+        .get_single()
+        .ok()
+        .and_then(|handle| levels.get(handle))
+    {
+        Some(level) => level,
+        None => return,
+    };
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+
+    let position = camera.translation.truncate();
+    let offset = player - position;
+    let target = position + offset - offset.clamp(-DEAD_ZONE, DEAD_ZONE);
+    move_camera(
+        &mut camera,
+        projection,
+        target,
+        time.delta_seconds(),
+        window,
+        level,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn focus_door_system(
+    time: Res<Time>,
+    windows: Res<Windows>,
+    levels: Res<Assets<LdtkLevel>>,
+    mut focus: ResMut<DoorFocus>,
+    mut events: EventReader<DoorOpened>,
+    level_info: Query<&Handle<LdtkLevel>>,
+    door_info: Query<&GlobalTransform>,
+    mut camera_info: Query<(&mut Transform, &OrthographicProjection), With<MainCamera>>,
+) {
+    for event in events.iter() {
+        **focus = door_info
+            .get(event.door)
+            .ok()
+            .map(|door| door.translation().truncate());
+    }
+    let target = match **focus {
+        Some(target) => target,
+        None => return,
+    };
+    let level = match level_info
+        .get_single()
+        .ok()
+        .and_then(|handle| levels.get(handle))
+    {
+        Some(level) => level,
+        None => return,
+    };
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let (mut camera, projection) = camera_info.single_mut();
+    move_camera(
+        &mut camera,
+        projection,
+        target,
+        time.delta_seconds(),
+        window,
+        level,
+    );
+}
+
+fn clear_door_focus_system(mut focus: ResMut<DoorFocus>) {
+    **focus = None;
+}
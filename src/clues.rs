@@ -0,0 +1,271 @@
+use crate::{
+    actions::{Action, Actions, InputBindings},
+    camera::MainCamera,
+    map::{clue_image_name, ClueImage, ClueRegion, ClueText, CurrentLevel},
+    menus::menu_text,
+    player::Player,
+    riddles::TouchDoor,
+    GameState,
+};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct CluesPlugin;
+
+impl Plugin for CluesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentClue>()
+            .init_resource::<InspectedClues>()
+            .add_system_set(
+                SystemSet::on_update(GameState::MapExploring)
+                    .with_system(inspect_clue_system.after(TouchDoor)),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::ClueInspecting)
+                    .with_system(spawn_clue_overlay_system)
+                    .with_system(stop_player_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::ClueInspecting).with_system(close_clue_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::ClueInspecting).with_system(despawn_clue_system),
+            );
+    }
+}
+
+/// How close the player has to stand to a box to inspect its clue.
+const INSPECT_DISTANCE: f32 = 96.0;
+/// How close the player has to stand to a picture of the clues layer to inspect it. Such
+/// pictures are often painted in the sky, out of the player's reach.
+const REGION_INSPECT_DISTANCE: f32 = 256.0;
+/// The size of the longer side of the zoomed clue image, relative to the window's height.
+const CLUE_SCALE: f32 = 0.6;
+const CLUE_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.75);
+/// How far in front of each other the stacked tiles of a clue region are drawn.
+const STACKED_TILE_DEPTH: f32 = 0.001;
+
+/// The box or clue region being inspected.
+#[derive(Default, Deref, DerefMut)]
+struct CurrentClue(Option<Entity>);
+
+/// The clues the player has inspected, by the iid of their box or the key of their region,
+/// listed in the journal.
+#[derive(Default, Deref, DerefMut)]
+pub struct InspectedClues(HashMap<String, ClueNote>);
+
+/// What the journal remembers of an inspected clue.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct ClueNote {
+    pub level: String,
+    /// Names the picture, so clues without text can be told apart.
+    #[serde(default)]
+    pub image: String,
+    pub text: Option<String>,
+}
+
+#[derive(Component)]
+struct ClueNode;
+
+#[allow(clippy::too_many_arguments)]
+fn inspect_clue_system(
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    mut actions: ResMut<Actions>,
+    mut state: ResMut<State<GameState>>,
+    mut current_clue: ResMut<CurrentClue>,
+    mut inspected_clues: ResMut<InspectedClues>,
+    current_level: Res<CurrentLevel>,
+    ldtk_info: Query<&Handle<LdtkAsset>>,
+    player_info: Query<&GlobalTransform, With<Player>>,
+    clues: Query<(
+        Entity,
+        &GlobalTransform,
+        &ClueImage,
+        &ClueText,
+        &EntityInstance,
+    )>,
+    regions: Query<(Entity, &GlobalTransform, &ClueRegion)>,
+) {
+    if !actions.just_pressed(Action::Interact) {
+        return;
+    }
+    let player = player_info.single().translation().truncate();
+    let project = ldtk_info
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_assets.get(handle))
+        .map(|ldtk| &ldtk.project);
+    let boxes = clues.iter().filter_map(
+        |(entity, transform, clue_image, clue_text, entity_instance)| {
+            let distance = transform.translation().truncate().distance(player);
+            let image = clue_image.0.as_ref()?;
+            (distance <= INSPECT_DISTANCE).then(|| {
+                let note = ClueNote {
+                    level: current_level.clone(),
+                    image: project
+                        .map(|project| clue_image_name(project, image))
+                        .unwrap_or_default(),
+                    text: clue_text.0.clone(),
+                };
+                (entity, distance, entity_instance.iid.clone(), note)
+            })
+        },
+    );
+    let regions = regions.iter().filter_map(|(entity, transform, region)| {
+        // Measured to the closest point of the region, since pictures can be wide.
+        let distance = ((transform.translation().truncate() - player).abs() - region.size / 2.0)
+            .max(Vec2::ZERO)
+            .length();
+        (distance <= REGION_INSPECT_DISTANCE).then(|| {
+            let note = ClueNote {
+                level: current_level.clone(),
+                image: region.name.clone(),
+                text: None,
+            };
+            (entity, distance, region.key.clone(), note)
+        })
+    });
+    let nearest = boxes
+        .chain(regions)
+        .min_by(|(_, a, _, _), (_, b, _, _)| a.total_cmp(b));
+    if let Some((clue, _, key, note)) = nearest {
+        if !actions.open_screen(Action::Interact, &mut state, GameState::ClueInspecting) {
+            return;
+        }
+        inspected_clues.entry(key).or_insert(note);
+        **current_clue = Some(clue);
+    }
+}
+
+/// Keeps the player from sliding on once physics resumes after the clue is closed.
+fn stop_player_system(mut player_info: Query<&mut Velocity, With<Player>>) {
+    for mut velocity in player_info.iter_mut() {
+        velocity.linvel.x = 0.0;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_clue_overlay_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    windows: Res<Windows>,
+    bindings: Res<InputBindings>,
+    current_clue: Res<CurrentClue>,
+    clues: Query<(&Handle<TextureAtlas>, &TextureAtlasSprite, &ClueText)>,
+    regions: Query<(&Handle<TextureAtlas>, &ClueRegion)>,
+    camera_info: Query<(&Transform, &OrthographicProjection), With<MainCamera>>,
+) {
+    let clue = match **current_clue {
+        Some(clue) => clue,
+        None => return,
+    };
+    let window = match windows.get_primary() {
+        Some(window) => window,
+        None => return,
+    };
+    let (camera, projection) = camera_info.single();
+    let view = Vec2::new(window.width(), window.height()) * projection.scale;
+    // The overlay is drawn in the world, right in front of the camera, since UI images can't
+    // show a tile of a texture atlas.
+    let position = camera.translation.truncate();
+    let depth = camera.translation.z - 1.0;
+    let text = if let Ok((texture_atlas, sprite, clue_text)) = clues.get(clue) {
+        commands
+            .spawn_bundle(SpriteSheetBundle {
+                sprite: TextureAtlasSprite {
+                    index: sprite.index,
+                    custom_size: sprite
+                        .custom_size
+                        .map(|size| size / size.max_element() * view.y * CLUE_SCALE),
+                    ..Default::default()
+                },
+                texture_atlas: texture_atlas.clone(),
+                transform: Transform::from_translation(position.extend(depth)),
+                ..Default::default()
+            })
+            .insert(ClueNode);
+        clue_text.0.clone()
+    } else if let Ok((texture_atlas, region)) = regions.get(clue) {
+        let scale = view.y * CLUE_SCALE / region.size.max_element();
+        for (layer, tile) in region.tiles.iter().enumerate() {
+            commands
+                .spawn_bundle(SpriteSheetBundle {
+                    sprite: TextureAtlasSprite {
+                        index: tile.index,
+                        custom_size: Some(Vec2::splat(region.tile_size * scale)),
+                        ..Default::default()
+                    },
+                    texture_atlas: texture_atlas.clone(),
+                    transform: Transform::from_translation(
+                        (position + tile.offset * scale)
+                            .extend(depth + layer as f32 * STACKED_TILE_DEPTH),
+                    ),
+                    ..Default::default()
+                })
+                .insert(ClueNode);
+        }
+        None
+    } else {
+        return;
+    };
+    commands
+        .spawn_bundle(SpriteBundle {
+            sprite: Sprite {
+                color: CLUE_BACKGROUND_COLOR,
+                custom_size: Some(view),
+                ..Default::default()
+            },
+            transform: Transform::from_translation(position.extend(depth - 1.0)),
+            ..Default::default()
+        })
+        .insert(ClueNode);
+
+    let close_inputs = bindings.description(Action::Cancel);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::FlexEnd,
+                align_items: AlignItems::Center,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                padding: UiRect::all(Val::Px(20.0)),
+                ..Default::default()
+            },
+            color: UiColor::from(Color::NONE),
+            ..Default::default()
+        })
+        .insert(ClueNode)
+        .with_children(|parent| {
+            if let Some(text) = text {
+                parent.spawn_bundle(menu_text(&asset_server, text, 40.0));
+            }
+            parent.spawn_bundle(menu_text(
+                &asset_server,
+                format!("Press {} to close", close_inputs),
+                24.0,
+            ));
+        });
+}
+
+fn close_clue_system(mut actions: ResMut<Actions>, mut state: ResMut<State<GameState>>) {
+    for action in [Action::Cancel, Action::Interact] {
+        if actions.just_pressed(action) {
+            actions.consume(action);
+            state.set(GameState::MapExploring).unwrap();
+            return;
+        }
+    }
+}
+
+fn despawn_clue_system(
+    mut commands: Commands,
+    mut current_clue: ResMut<CurrentClue>,
+    clue_nodes: Query<Entity, With<ClueNode>>,
+) {
+    **current_clue = None;
+    for clue_node in clue_nodes.iter() {
+        commands.entity(clue_node).despawn_recursive();
+    }
+}
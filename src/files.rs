@@ -0,0 +1,12 @@
+use serde::Serialize;
+use std::{fs, path::Path};
+
+/// Writes a value to a RON file, creating the directories leading to it.
+pub fn write_ron<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let contents = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|error| error.to_string())?;
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory).map_err(|error| error.to_string())?;
+    }
+    fs::write(path, contents).map_err(|error| error.to_string())
+}
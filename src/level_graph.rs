@@ -0,0 +1,297 @@
+use crate::{
+    map::{Fields, STARTING_LEVEL},
+    validation::level_entities,
+};
+use bevy::utils::{HashMap, HashSet};
+use bevy_ecs_ldtk::ldtk::{LdtkJson, Level};
+use std::{collections::VecDeque, fmt::Write};
+
+const WIN_TEXT: &str = "You win!";
+
+/// A door leading from one level to another, identified by the iids used in `map.ldtk`.
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub door: String,
+    pub question: String,
+}
+
+pub struct LevelGraph<'a> {
+    levels: Vec<&'a Level>,
+    edges: Vec<Edge>,
+}
+
+impl<'a> LevelGraph<'a> {
+    pub fn new(project: &'a LdtkJson) -> Self {
+        let edges = project
+            .levels
+            .iter()
+            .flat_map(|level| {
+                level_entities(level)
+                    .filter(|entity| entity.identifier == "Door")
+                    .filter_map(|door| {
+                        let fields = Fields::new(door);
+                        Some(Edge {
+                            from: level.iid.clone(),
+                            to: fields.string("next_level").ok()?,
+                            door: door.iid.clone(),
+                            question: fields.string("question").unwrap_or_default(),
+                        })
+                    })
+            })
+            .collect();
+        Self {
+            levels: project.levels.iter().collect(),
+            edges,
+        }
+    }
+
+    pub fn name(&self, level_iid: &str) -> String {
+        self.levels
+            .iter()
+            .find(|level| level.iid == level_iid)
+            .map(|level| level.identifier.clone())
+            .unwrap_or_else(|| format!("<missing {}>", level_iid))
+    }
+
+    fn outgoing<'b>(&'b self, level_iid: &'b str) -> impl Iterator<Item = &'b Edge> {
+        self.edges.iter().filter(move |edge| edge.from == level_iid)
+    }
+
+    /// The level that congratulates the player, recognised by its text sign.
+    pub fn win_level(&self) -> Option<&'a Level> {
+        self.levels.iter().copied().find(|level| {
+            level_entities(level)
+                .filter(|entity| entity.identifier == "TextSign")
+                .any(|sign| Fields::new(sign).string("text").ok().as_deref() == Some(WIN_TEXT))
+        })
+    }
+
+    /// Finds the fewest doors leading from the starting level to `target`.
+    pub fn shortest_path(&self, target: &str) -> Option<Vec<&Edge>> {
+        let mut previous = HashMap::<&str, &Edge>::new();
+        let mut visited = HashSet::from_iter([STARTING_LEVEL]);
+        let mut queue = VecDeque::from([STARTING_LEVEL]);
+        while let Some(level) = queue.pop_front() {
+            if level == target {
+                let mut path = Vec::new();
+                let mut current = level;
+                while let Some(edge) = previous.get(current) {
+                    path.push(*edge);
+                    current = edge.from.as_str();
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for edge in self.outgoing(level) {
+                if visited.insert(edge.to.as_str()) {
+                    previous.insert(edge.to.as_str(), edge);
+                    queue.push_back(edge.to.as_str());
+                }
+            }
+        }
+        None
+    }
+
+    pub fn unreachable_levels(&self) -> Vec<&'a Level> {
+        self.levels
+            .iter()
+            .copied()
+            .filter(|level| self.shortest_path(&level.iid).is_none())
+            .collect()
+    }
+
+    /// Levels the player can reach but can't get to the winning level from, either because
+    /// they have no door out or because their doors only lead in circles.
+    pub fn dead_ends(&self) -> Vec<&'a Level> {
+        match self.win_level() {
+            Some(win_level) => self.levels_stuck_before(&win_level.iid),
+            // Missing the winning level is reported on its own.
+            None => Vec::new(),
+        }
+    }
+
+    fn levels_stuck_before(&self, target: &str) -> Vec<&'a Level> {
+        // Walks the doors backwards from the target to find every level that leads to it.
+        let mut leading = HashSet::from_iter([target]);
+        let mut queue = VecDeque::from([target]);
+        while let Some(level) = queue.pop_front() {
+            for edge in self.edges.iter().filter(|edge| edge.to == level) {
+                if leading.insert(edge.from.as_str()) {
+                    queue.push_back(edge.from.as_str());
+                }
+            }
+        }
+        self.levels
+            .iter()
+            .copied()
+            .filter(|level| !leading.contains(level.iid.as_str()))
+            .filter(|level| self.shortest_path(&level.iid).is_some())
+            .collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph levels {\n");
+        for level in self.levels.iter() {
+            let shape = if level.iid == STARTING_LEVEL {
+                "doublecircle"
+            } else {
+                "circle"
+            };
+            writeln!(
+                dot,
+                "    \"{}\" [label=\"{}\", shape={}];",
+                level.iid, level.identifier, shape
+            )
+            .unwrap();
+        }
+        for edge in self.edges.iter() {
+            writeln!(
+                dot,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                edge.from,
+                edge.to,
+                edge.question.replace('"', "\\\"").replace('\n', "\\n")
+            )
+            .unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(iid: &str) -> Level {
+        Level {
+            iid: iid.to_string(),
+            identifier: iid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn edge(from: &str, to: &str, door: &str) -> Edge {
+        Edge {
+            from: from.to_string(),
+            to: to.to_string(),
+            door: door.to_string(),
+            question: String::new(),
+        }
+    }
+
+    fn doors(path: Option<Vec<&Edge>>) -> Option<Vec<&str>> {
+        path.map(|path| Vec::from_iter(path.into_iter().map(|edge| edge.door.as_str())))
+    }
+
+    fn iids(levels: Vec<&Level>) -> Vec<&str> {
+        let mut iids = Vec::from_iter(levels.into_iter().map(|level| level.iid.as_str()));
+        iids.sort();
+        iids
+    }
+
+    /// start -> a -> b -> end, with a shortcut from start to b, and an island nothing leads to.
+    fn graph(levels: &[Level]) -> LevelGraph<'_> {
+        LevelGraph {
+            levels: levels.iter().collect(),
+            edges: vec![
+                edge(STARTING_LEVEL, "a", "start to a"),
+                edge("a", "b", "a to b"),
+                edge(STARTING_LEVEL, "b", "start to b"),
+                edge("b", "end", "b to end"),
+                edge("island", "a", "island to a"),
+            ],
+        }
+    }
+
+    fn levels() -> Vec<Level> {
+        vec![
+            level(STARTING_LEVEL),
+            level("a"),
+            level("b"),
+            level("end"),
+            level("island"),
+        ]
+    }
+
+    #[test]
+    fn starting_level_needs_no_doors() {
+        let levels = levels();
+        assert_eq!(
+            doors(graph(&levels).shortest_path(STARTING_LEVEL)),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn shortest_path_takes_the_fewest_doors() {
+        let levels = levels();
+        let graph = graph(&levels);
+        assert_eq!(doors(graph.shortest_path("a")), Some(vec!["start to a"]));
+        assert_eq!(
+            doors(graph.shortest_path("end")),
+            Some(vec!["start to b", "b to end"])
+        );
+    }
+
+    #[test]
+    fn shortest_path_does_not_walk_doors_backwards() {
+        let levels = levels();
+        assert_eq!(doors(graph(&levels).shortest_path("island")), None);
+    }
+
+    #[test]
+    fn unreachable_levels_have_no_path() {
+        let levels = levels();
+        assert_eq!(iids(graph(&levels).unreachable_levels()), vec!["island"]);
+    }
+
+    fn graph_with(levels: &[Level], edges: Vec<Edge>) -> LevelGraph<'_> {
+        LevelGraph {
+            levels: levels.iter().collect(),
+            edges,
+        }
+    }
+
+    #[test]
+    fn levels_leading_to_the_end_are_not_stuck() {
+        let levels = levels();
+        assert!(graph(&levels).levels_stuck_before("end").is_empty());
+    }
+
+    #[test]
+    fn levels_without_a_door_out_are_stuck() {
+        let levels = vec![level(STARTING_LEVEL), level("a"), level("end")];
+        let graph = graph_with(
+            &levels,
+            vec![
+                edge(STARTING_LEVEL, "a", "start to a"),
+                edge(STARTING_LEVEL, "end", "start to end"),
+            ],
+        );
+        assert_eq!(iids(graph.levels_stuck_before("end")), vec!["a"]);
+    }
+
+    #[test]
+    fn levels_in_a_trap_cycle_are_stuck() {
+        let levels = vec![
+            level(STARTING_LEVEL),
+            level("a"),
+            level("b"),
+            level("end"),
+            level("island"),
+        ];
+        let graph = graph_with(
+            &levels,
+            vec![
+                edge(STARTING_LEVEL, "a", "start to a"),
+                edge("a", "b", "a to b"),
+                edge("b", "a", "b to a"),
+                edge(STARTING_LEVEL, "end", "start to end"),
+                edge("island", "island", "island to island"),
+            ],
+        );
+        assert_eq!(iids(graph.levels_stuck_before("end")), vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,40 @@
+pub mod actions;
+pub mod animation;
+pub mod camera;
+pub mod clues;
+pub mod files;
+pub mod level_graph;
+pub mod map;
+pub mod menus;
+pub mod player;
+pub mod progress;
+pub mod riddles;
+pub mod validation;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum GameState {
+    MainMenu,
+    Settings,
+    ProfileSelecting,
+    MapExploring,
+    RiddleSolving,
+    LevelLoading,
+    DoorOpening,
+    ClueInspecting,
+    Paused,
+    Journal,
+}
+
+impl GameState {
+    /// Whether a level is being played, as opposed to the game sitting in a menu.
+    pub fn is_playing(&self) -> bool {
+        matches!(
+            self,
+            Self::MapExploring
+                | Self::RiddleSolving
+                | Self::LevelLoading
+                | Self::DoorOpening
+                | Self::ClueInspecting
+        )
+    }
+}
@@ -1,38 +1,31 @@
-use bevy::prelude::*;
+use bevy::{asset::AssetServerSettings, prelude::*};
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
-use map::MapPlugin;
-use player::PlayerPlugin;
-use riddles::RiddlesPlugin;
-
-mod map;
-mod player;
-mod riddles;
-
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
-enum GameState {
-    MapExploring,
-    RiddleSolving,
-    LevelLoading,
-}
+use puzzle_up::{
+    actions::ActionsPlugin, animation::SpriteAnimationPlugin, camera::CameraPlugin,
+    clues::CluesPlugin, map::MapPlugin, menus::MenusPlugin, player::PlayerPlugin,
+    progress::ProgressPlugin, riddles::RiddlesPlugin, GameState,
+};
 
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins)
-        .add_state(GameState::LevelLoading)
-        .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
-        .insert_resource(RapierConfiguration {
-            gravity: Vec2::new(0.0, -400.0),
+        .insert_resource(AssetServerSettings {
+            // Lets designers tune assets such as the player config while the game runs.
+            watch_for_changes: cfg!(debug_assertions),
             ..Default::default()
         })
+        .add_plugins(DefaultPlugins)
+        .add_state(GameState::MainMenu)
+        .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
         .add_plugin(LdtkPlugin)
-        .add_startup_system(setup_system)
+        .add_plugin(ActionsPlugin)
+        .add_plugin(SpriteAnimationPlugin)
+        .add_plugin(CameraPlugin)
         .add_plugin(PlayerPlugin)
         .add_plugin(MapPlugin)
         .add_plugin(RiddlesPlugin)
+        .add_plugin(CluesPlugin)
+        .add_plugin(ProgressPlugin)
+        .add_plugin(MenusPlugin)
         .run();
 }
-
-fn setup_system(mut commands: Commands) {
-    commands.spawn_bundle(Camera2dBundle::default());
-}
@@ -0,0 +1,336 @@
+use super::{from_entity_or_default, EntityError, FieldProblem, Fields, Ground, LARGE_TILE_SIZE};
+use bevy::{prelude::*, sprite::Rect, utils::HashMap};
+use bevy_ecs_ldtk::{
+    ldtk::{LdtkJson, TilesetRectangle},
+    prelude::*,
+};
+use bevy_rapier2d::prelude::*;
+
+pub struct BoxesPlugin;
+
+impl Plugin for BoxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BoxAtlases>()
+            .init_resource::<ClueAtlases>()
+            .add_system(box_image_system);
+    }
+}
+
+const BOX_TYPE_ENUM: &str = "BoxType";
+/// The tile fields a clue picture can be picked with, one per clue tilesheet since LDtk ties
+/// each tile field to a single tileset.
+const CLUE_IMAGE_FIELDS: [&str; 3] = ["cloud_clue", "fruit_clue", "card_clue"];
+
+const PUSHABLE_BOX_DENSITY: f32 = 0.5;
+const PUSHABLE_BOX_FRICTION: f32 = 0.8;
+const PUSHABLE_BOX_DAMPING: f32 = 1.0;
+
+#[derive(Default, Bundle, LdtkEntity)]
+pub struct BoxTile {
+    #[sprite_sheet_bundle]
+    #[bundle]
+    sprite_sheet_bundle: SpriteSheetBundle,
+    #[from_entity_instance]
+    #[bundle]
+    box_body: BoxBody,
+    ground: Ground,
+    #[from_entity_instance]
+    box_image: BoxImage,
+    #[from_entity_instance]
+    clue_image: ClueImage,
+    #[from_entity_instance]
+    clue_text: ClueText,
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
+}
+
+/// Texture atlases of the tilesets box images are taken from, by tileset uid.
+#[derive(Default, Deref, DerefMut)]
+pub(super) struct BoxAtlases(HashMap<i32, Handle<TextureAtlas>>);
+
+/// Single-image texture atlases of the clue pictures shown on boxes, by tileset uid and rectangle.
+#[derive(Default, Deref, DerefMut)]
+struct ClueAtlases(HashMap<(i32, i32, i32, i32, i32), Handle<TextureAtlas>>);
+
+/// The `BoxType` value naming the image shown on a box.
+#[derive(Default, Component)]
+pub struct BoxImage(pub String);
+
+impl TryFrom<&EntityInstance> for BoxImage {
+    type Error = EntityError;
+
+    fn try_from(entity_instance: &EntityInstance) -> Result<Self, Self::Error> {
+        Ok(Self(
+            Fields::new(entity_instance)
+                .optional_string("BoxImage")?
+                .unwrap_or_default(),
+        ))
+    }
+}
+
+impl From<EntityInstance> for BoxImage {
+    fn from(entity_instance: EntityInstance) -> Self {
+        from_entity_or_default(&entity_instance)
+    }
+}
+
+/// A picture from one of the clue tilesheets, shown on a box instead of its `BoxType` image.
+/// Only boxes with a picture can be inspected.
+#[derive(Default, Clone, Component)]
+pub struct ClueImage(pub Option<TilesetRectangle>);
+
+impl TryFrom<&EntityInstance> for ClueImage {
+    type Error = EntityError;
+
+    fn try_from(entity_instance: &EntityInstance) -> Result<Self, Self::Error> {
+        let fields = Fields::new(entity_instance);
+        let mut images = Vec::new();
+        for field in CLUE_IMAGE_FIELDS {
+            if let Some(image) = fields.optional_tile(field)? {
+                images.push((field, image));
+            }
+        }
+        if images.len() > 1 {
+            return Err(fields.error(
+                images[1].0,
+                FieldProblem::Invalid(format!(
+                    "a box can only show one clue, {} is already set",
+                    images[0].0
+                )),
+            ));
+        }
+        Ok(Self(images.pop().map(|(_, image)| image)))
+    }
+}
+
+impl From<EntityInstance> for ClueImage {
+    fn from(entity_instance: EntityInstance) -> Self {
+        from_entity_or_default(&entity_instance)
+    }
+}
+
+/// Flavor text shown next to a box's image when the player inspects it.
+#[derive(Default, Component)]
+pub struct ClueText(pub Option<String>);
+
+impl TryFrom<&EntityInstance> for ClueText {
+    type Error = EntityError;
+
+    fn try_from(entity_instance: &EntityInstance) -> Result<Self, Self::Error> {
+        Ok(Self(
+            Fields::new(entity_instance)
+                .optional_string("clue_text")?
+                .filter(|text| !text.trim().is_empty()),
+        ))
+    }
+}
+
+impl From<EntityInstance> for ClueText {
+    fn from(entity_instance: EntityInstance) -> Self {
+        from_entity_or_default(&entity_instance)
+    }
+}
+
+/// Finds the tileset uid and tile id of the icon the project gives a `BoxType` value.
+pub fn box_image_tile(project: &LdtkJson, box_type: &str) -> Option<(i32, i32)> {
+    let definition = project
+        .defs
+        .enums
+        .iter()
+        .find(|definition| definition.identifier == BOX_TYPE_ENUM)?;
+    let value = definition
+        .values
+        .iter()
+        .find(|value| value.id == box_type)?;
+    Some((definition.icon_tileset_uid?, value.tile_id?))
+}
+
+/// Names a clue picture after its tilesheet and the position of its first tile in it.
+pub fn clue_image_name(project: &LdtkJson, image: &TilesetRectangle) -> String {
+    match project
+        .defs
+        .tilesets
+        .iter()
+        .find(|tileset| tileset.uid == image.tileset_uid)
+    {
+        Some(tileset) => {
+            let column = image.x / tileset.tile_grid_size;
+            let row = image.y / tileset.tile_grid_size;
+            format!(
+                "{} picture {}",
+                tileset.identifier,
+                row * tileset.c_wid + column + 1
+            )
+        }
+        None => "a picture".to_string(),
+    }
+}
+
+fn box_atlas(
+    ldtk_asset: &LdtkAsset,
+    tileset_uid: i32,
+    texture_atlases: &mut Assets<TextureAtlas>,
+) -> Option<Handle<TextureAtlas>> {
+    let tileset = ldtk_asset
+        .project
+        .defs
+        .tilesets
+        .iter()
+        .find(|tileset| tileset.uid == tileset_uid)?;
+    let texture = ldtk_asset.tileset_map.get(&tileset_uid)?.clone();
+    Some(texture_atlases.add(TextureAtlas::from_grid_with_padding(
+        texture,
+        Vec2::splat(tileset.tile_grid_size as f32),
+        tileset.c_wid as usize,
+        tileset.c_hei as usize,
+        Vec2::splat(tileset.spacing as f32),
+        Vec2::splat(tileset.padding as f32),
+    )))
+}
+
+/// Looks up the texture atlas of a tileset, creating it the first time it is needed.
+pub(super) fn tileset_atlas(
+    ldtk_asset: &LdtkAsset,
+    tileset_uid: i32,
+    box_atlases: &mut BoxAtlases,
+    texture_atlases: &mut Assets<TextureAtlas>,
+) -> Option<Handle<TextureAtlas>> {
+    if let Some(box_atlas) = box_atlases.get(&tileset_uid) {
+        return Some(box_atlas.clone());
+    }
+    let box_atlas = box_atlas(ldtk_asset, tileset_uid, texture_atlases)?;
+    box_atlases.insert(tileset_uid, box_atlas.clone());
+    Some(box_atlas)
+}
+
+fn clue_atlas(
+    ldtk_asset: &LdtkAsset,
+    image: &TilesetRectangle,
+    texture_atlases: &mut Assets<TextureAtlas>,
+) -> Option<Handle<TextureAtlas>> {
+    let tileset = ldtk_asset
+        .project
+        .defs
+        .tilesets
+        .iter()
+        .find(|tileset| tileset.uid == image.tileset_uid)?;
+    let texture = ldtk_asset.tileset_map.get(&image.tileset_uid)?.clone();
+    let mut atlas = TextureAtlas::new_empty(
+        texture,
+        Vec2::new(tileset.px_wid as f32, tileset.px_hei as f32),
+    );
+    let min = Vec2::new(image.x as f32, image.y as f32);
+    atlas.add_texture(Rect {
+        min,
+        max: min + Vec2::new(image.w as f32, image.h as f32),
+    });
+    Some(texture_atlases.add(atlas))
+}
+
+#[allow(clippy::type_complexity)]
+fn box_image_system(
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut box_atlases: ResMut<BoxAtlases>,
+    mut clue_atlases: ResMut<ClueAtlases>,
+    world_info: Query<&Handle<LdtkAsset>>,
+    mut boxes: Query<
+        (
+            &BoxImage,
+            &ClueImage,
+            &mut Handle<TextureAtlas>,
+            &mut TextureAtlasSprite,
+        ),
+        Added<BoxImage>,
+    >,
+) {
+    let ldtk_asset = match world_info
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_assets.get(handle))
+    {
+        Some(ldtk_asset) => ldtk_asset,
+        None => return,
+    };
+    for (box_image, clue_image, mut atlas, mut sprite) in boxes.iter_mut() {
+        if let Some(image) = &clue_image.0 {
+            let key = (image.tileset_uid, image.x, image.y, image.w, image.h);
+            let clue_atlas = match clue_atlases.get(&key) {
+                Some(clue_atlas) => clue_atlas.clone(),
+                None => match clue_atlas(ldtk_asset, image, &mut texture_atlases) {
+                    Some(clue_atlas) => {
+                        clue_atlases.insert(key, clue_atlas.clone());
+                        clue_atlas
+                    }
+                    None => continue,
+                },
+            };
+            *atlas = clue_atlas;
+            sprite.index = 0;
+            // Fit the picture into the box without stretching it.
+            let size = Vec2::new(image.w as f32, image.h as f32);
+            sprite.custom_size = Some(size * LARGE_TILE_SIZE / size.max_element());
+            continue;
+        }
+        let (tileset_uid, tile_id) = match box_image_tile(&ldtk_asset.project, &box_image.0) {
+            Some(tile) => tile,
+            None => continue,
+        };
+        *atlas = match tileset_atlas(
+            ldtk_asset,
+            tileset_uid,
+            &mut box_atlases,
+            &mut texture_atlases,
+        ) {
+            Some(box_atlas) => box_atlas,
+            None => continue,
+        };
+        sprite.index = tile_id as usize;
+        // Draw every image at the size of a box, whatever the grid of its tileset.
+        sprite.custom_size = Some(Vec2::splat(LARGE_TILE_SIZE));
+    }
+}
+
+/// The physics of a box, which is fixed in place unless its `pushable` field is set.
+#[derive(Default, Bundle)]
+pub struct BoxBody {
+    collider: Collider,
+    rigid_body: RigidBody,
+    mass_properties: ColliderMassProperties,
+    friction: Friction,
+    damping: Damping,
+    locked_axes: LockedAxes,
+}
+
+impl TryFrom<&EntityInstance> for BoxBody {
+    type Error = EntityError;
+
+    fn try_from(entity_instance: &EntityInstance) -> Result<Self, Self::Error> {
+        let fields = Fields::new(entity_instance);
+        let collider = Collider::cuboid(LARGE_TILE_SIZE / 2.0, LARGE_TILE_SIZE / 2.0);
+        if !fields.optional_bool("pushable")?.unwrap_or_default() {
+            return Ok(Self {
+                collider,
+                rigid_body: RigidBody::Fixed,
+                ..Default::default()
+            });
+        }
+        Ok(Self {
+            collider,
+            rigid_body: RigidBody::Dynamic,
+            mass_properties: ColliderMassProperties::Density(PUSHABLE_BOX_DENSITY),
+            friction: Friction::coefficient(PUSHABLE_BOX_FRICTION),
+            damping: Damping {
+                linear_damping: PUSHABLE_BOX_DAMPING,
+                angular_damping: 0.0,
+            },
+            locked_axes: LockedAxes::ROTATION_LOCKED,
+        })
+    }
+}
+
+impl From<EntityInstance> for BoxBody {
+    fn from(entity_instance: EntityInstance) -> Self {
+        from_entity_or_default(&entity_instance)
+    }
+}
@@ -0,0 +1,243 @@
+use super::boxes::{tileset_atlas, BoxAtlases};
+use crate::GameState;
+use bevy::{prelude::*, utils::HashSet};
+use bevy_ecs_ldtk::{
+    ldtk::{LayerInstance, LdtkJson},
+    prelude::*,
+};
+
+pub struct ClueRegionsPlugin;
+
+impl Plugin for ClueRegionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_exit(GameState::LevelLoading).with_system(spawn_clue_regions_system),
+        );
+    }
+}
+
+const CLUES_LAYER: &str = "Clues";
+/// How many cells apart tiles of the clues layer may be to still belong to the same picture.
+const REGION_GAP: i32 = 2;
+
+/// A tile of a clue region, placed relative to the center of the region.
+pub struct ClueTile {
+    pub index: usize,
+    pub offset: Vec2,
+}
+
+/// A picture painted in the level's clues layer, inspected as a whole.
+#[derive(Component)]
+pub struct ClueRegion {
+    /// Identifies the region in the journal, by its layer and the position of its first tile.
+    pub key: String,
+    /// Names the region after its tilesheet and its place among the level's regions.
+    pub name: String,
+    pub size: Vec2,
+    pub tile_size: f32,
+    /// The tiles of the region, bottom to top where they are stacked.
+    pub tiles: Vec<ClueTile>,
+}
+
+/// Groups cells into regions of cells that are at most `REGION_GAP` cells apart.
+fn group_cells(cells: &[(i32, i32)]) -> Vec<Vec<(i32, i32)>> {
+    let mut remaining = HashSet::from_iter(cells.iter().copied());
+    let mut regions = Vec::new();
+    let mut sorted = Vec::from_iter(cells.iter().copied());
+    sorted.sort();
+    for start in sorted {
+        if !remaining.remove(&start) {
+            continue;
+        }
+        let mut region = vec![start];
+        let mut next = 0;
+        while next < region.len() {
+            let (x, y) = region[next];
+            next += 1;
+            let neighbours =
+                Vec::from_iter(remaining.iter().copied().filter(|(other_x, other_y)| {
+                    (other_x - x).abs() <= REGION_GAP && (other_y - y).abs() <= REGION_GAP
+                }));
+            for neighbour in neighbours {
+                remaining.remove(&neighbour);
+                region.push(neighbour);
+            }
+        }
+        region.sort();
+        regions.push(region);
+    }
+    regions
+}
+
+fn clue_regions(
+    layer: &LayerInstance,
+    level_height: f32,
+    project: &LdtkJson,
+) -> Vec<(Vec2, ClueRegion)> {
+    let tileset = project
+        .defs
+        .tilesets
+        .iter()
+        .find(|tileset| Some(tileset.uid) == layer.tileset_def_uid)
+        .map(|tileset| tileset.identifier.as_str())
+        .unwrap_or("Clue");
+    let grid_size = layer.grid_size;
+    let tile_size = grid_size as f32;
+    let cells = Vec::from_iter(
+        layer
+            .grid_tiles
+            .iter()
+            .map(|tile| (tile.px.x / grid_size, tile.px.y / grid_size)),
+    );
+    group_cells(&cells)
+        .into_iter()
+        .enumerate()
+        .map(|(number, region)| {
+            let region = HashSet::<(i32, i32)>::from_iter(region);
+            let tiles =
+                Vec::from_iter(layer.grid_tiles.iter().filter(|tile| {
+                    region.contains(&(tile.px.x / grid_size, tile.px.y / grid_size))
+                }));
+            let min = tiles
+                .iter()
+                .map(|tile| tile.px)
+                .reduce(IVec2::min)
+                .unwrap_or_default();
+            let max = tiles
+                .iter()
+                .map(|tile| tile.px)
+                .reduce(IVec2::max)
+                .unwrap_or_default();
+            let size = (max - min).as_vec2() + tile_size;
+            // LDtk measures from the top of the level, while the level's entities go up.
+            let top_left = Vec2::new(min.x as f32, level_height - min.y as f32);
+            let center = top_left + Vec2::new(size.x, -size.y) / 2.0;
+            let first = tiles[0];
+            (
+                center,
+                ClueRegion {
+                    key: format!("{}@{},{}", layer.iid, first.px.x, first.px.y),
+                    name: format!("{} region {}", tileset, number + 1),
+                    size,
+                    tile_size,
+                    tiles: tiles
+                        .iter()
+                        .map(|tile| ClueTile {
+                            index: tile.t as usize,
+                            offset: Vec2::new(
+                                tile.px.x as f32 + tile_size / 2.0,
+                                level_height - tile.px.y as f32 - tile_size / 2.0,
+                            ) - center,
+                        })
+                        .collect(),
+                },
+            )
+        })
+        .collect()
+}
+
+fn spawn_clue_regions_system(
+    mut commands: Commands,
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    levels: Res<Assets<LdtkLevel>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut box_atlases: ResMut<BoxAtlases>,
+    world_info: Query<&Handle<LdtkAsset>>,
+    level_info: Query<(Entity, &Handle<LdtkLevel>)>,
+) {
+    let ldtk_asset = match world_info
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_assets.get(handle))
+    {
+        Some(ldtk_asset) => ldtk_asset,
+        None => return,
+    };
+    let (level_entity, level) = match level_info
+        .get_single()
+        .ok()
+        .and_then(|(entity, handle)| Some((entity, levels.get(handle)?)))
+    {
+        Some(level) => level,
+        None => return,
+    };
+    let layer = match level
+        .level
+        .layer_instances
+        .iter()
+        .flatten()
+        .find(|layer| layer.identifier == CLUES_LAYER)
+    {
+        Some(layer) => layer,
+        None => return,
+    };
+    let atlas = match layer.tileset_def_uid.and_then(|tileset_uid| {
+        tileset_atlas(
+            ldtk_asset,
+            tileset_uid,
+            &mut box_atlases,
+            &mut texture_atlases,
+        )
+    }) {
+        Some(atlas) => atlas,
+        None => return,
+    };
+    let regions = clue_regions(layer, level.level.px_hei as f32, &ldtk_asset.project);
+    commands.entity(level_entity).with_children(|parent| {
+        for (center, region) in regions {
+            parent
+                .spawn_bundle(TransformBundle::from(Transform::from_translation(
+                    center.extend(0.0),
+                )))
+                .insert(atlas.clone())
+                .insert(region);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions(cells: &[(i32, i32)]) -> Vec<Vec<(i32, i32)>> {
+        let mut regions = group_cells(cells);
+        regions.sort();
+        regions
+    }
+
+    #[test]
+    fn no_cells_give_no_regions() {
+        assert!(group_cells(&[]).is_empty());
+    }
+
+    #[test]
+    fn joins_touching_cells() {
+        assert_eq!(
+            regions(&[(0, 0), (1, 0), (1, 1)]),
+            vec![vec![(0, 0), (1, 0), (1, 1)]]
+        );
+    }
+
+    #[test]
+    fn joins_cells_across_a_small_gap() {
+        assert_eq!(regions(&[(0, 0), (2, 2)]), vec![vec![(0, 0), (2, 2)]]);
+    }
+
+    #[test]
+    fn splits_cells_across_a_wide_gap() {
+        assert_eq!(regions(&[(0, 0), (3, 0)]), vec![vec![(0, 0)], vec![(3, 0)]]);
+    }
+
+    #[test]
+    fn joins_chains_of_cells() {
+        assert_eq!(
+            regions(&[(0, 0), (2, 0), (4, 0), (8, 0)]),
+            vec![vec![(0, 0), (2, 0), (4, 0)], vec![(8, 0)]]
+        );
+    }
+
+    #[test]
+    fn counts_stacked_tiles_once() {
+        assert_eq!(regions(&[(0, 0), (0, 0)]), vec![vec![(0, 0)]]);
+    }
+}
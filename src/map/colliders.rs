@@ -0,0 +1,238 @@
+use super::{Ground, SMALL_TILE_SIZE};
+use crate::GameState;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+pub struct CollidersPlugin;
+
+impl Plugin for CollidersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_exit(GameState::LevelLoading).with_system(merge_colliders_system),
+        )
+        .register_ldtk_int_cell::<SolidCell>(SOLID_INT_GRID_VALUE);
+    }
+}
+
+const SOLID_INT_GRID_VALUE: i32 = 1;
+
+/// Marks the cells of the level that should be covered by merged colliders.
+#[derive(Default, Clone, Copy, Eq, PartialEq, Hash, Component)]
+pub enum Solid {
+    #[default]
+    Ground,
+    Border,
+}
+
+impl From<EntityInstance> for Solid {
+    fn from(entity_instance: EntityInstance) -> Self {
+        match entity_instance.identifier.as_ref() {
+            "LevelBorder" => Self::Border,
+            _ => Self::Ground,
+        }
+    }
+}
+
+#[derive(Default, Bundle, LdtkIntCell)]
+struct SolidCell {
+    solid: Solid,
+}
+
+struct CellRect {
+    left: i32,
+    right: i32,
+    bottom: i32,
+    top: i32,
+}
+
+fn entity_cells(transform: &Transform) -> impl Iterator<Item = (i32, i32)> {
+    let size = transform.scale.truncate() * SMALL_TILE_SIZE;
+    let corner = (transform.translation.truncate() - size / 2.0) / SMALL_TILE_SIZE;
+    let (left, bottom) = (corner.x.round() as i32, corner.y.round() as i32);
+    let (width, height) = (
+        transform.scale.x.round() as i32,
+        transform.scale.y.round() as i32,
+    );
+    (bottom..bottom + height).flat_map(move |y| (left..left + width).map(move |x| (x, y)))
+}
+
+/// Covers the cells with as few rectangles as possible by first joining every row into
+/// plates and then stacking plates of the same width on top of each other.
+fn merge_cells(cells: &HashSet<(i32, i32)>) -> Vec<CellRect> {
+    let mut rows = HashMap::<i32, Vec<i32>>::new();
+    for (x, y) in cells.iter() {
+        rows.entry(*y).or_default().push(*x);
+    }
+    let mut row_keys = Vec::from_iter(rows.keys().copied());
+    row_keys.sort();
+
+    let mut finished = Vec::new();
+    let mut open = HashMap::<(i32, i32), CellRect>::new();
+    let mut previous_row = None;
+    for y in row_keys {
+        let mut columns = rows.remove(&y).unwrap_or_default();
+        columns.sort();
+        let mut plates = Vec::new();
+        for x in columns {
+            match plates.last_mut() {
+                Some((_, right)) if *right + 1 == x => *right = x,
+                _ => plates.push((x, x)),
+            }
+        }
+
+        let mut next_open = HashMap::new();
+        for plate in plates {
+            let rect = match open.remove(&plate) {
+                Some(mut rect) if previous_row == Some(y - 1) => {
+                    rect.top = y;
+                    rect
+                }
+                stale => {
+                    finished.extend(stale);
+                    CellRect {
+                        left: plate.0,
+                        right: plate.1,
+                        bottom: y,
+                        top: y,
+                    }
+                }
+            };
+            next_open.insert(plate, rect);
+        }
+        finished.extend(open.into_values());
+        open = next_open;
+        previous_row = Some(y);
+    }
+    finished.extend(open.into_values());
+    finished
+}
+
+fn merge_colliders_system(
+    mut commands: Commands,
+    solid_entities: Query<(Entity, &Solid, &Transform), Without<IntGridCell>>,
+    solid_cells: Query<(&Solid, &GridCoords), With<IntGridCell>>,
+    level_info: Query<Entity, With<Handle<LdtkLevel>>>,
+) {
+    let level = match level_info.get_single() {
+        Ok(level) => level,
+        Err(_) => return,
+    };
+    let mut cells = HashMap::<Solid, HashSet<(i32, i32)>>::new();
+    for (entity, solid, transform) in solid_entities.iter() {
+        cells
+            .entry(*solid)
+            .or_default()
+            .extend(entity_cells(transform));
+        commands.entity(entity).despawn_recursive();
+    }
+    for (solid, grid_coords) in solid_cells.iter() {
+        cells
+            .entry(*solid)
+            .or_default()
+            .insert((grid_coords.x, grid_coords.y));
+    }
+
+    commands.entity(level).with_children(|parent| {
+        for (solid, cells) in cells.iter() {
+            for rect in merge_cells(cells) {
+                let width = (rect.right - rect.left + 1) as f32 * SMALL_TILE_SIZE;
+                let height = (rect.top - rect.bottom + 1) as f32 * SMALL_TILE_SIZE;
+                let mut collider = parent.spawn_bundle(TransformBundle::from(Transform::from_xyz(
+                    rect.left as f32 * SMALL_TILE_SIZE + width / 2.0,
+                    rect.bottom as f32 * SMALL_TILE_SIZE + height / 2.0,
+                    0.0,
+                )));
+                collider
+                    .insert(Collider::cuboid(width / 2.0, height / 2.0))
+                    .insert(RigidBody::Fixed);
+                if *solid == Solid::Ground {
+                    collider.insert(Ground);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(rows: &[&str]) -> HashSet<(i32, i32)> {
+        // The first row is the top one, like in the level editor.
+        let height = rows.len() as i32;
+        rows.iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .filter(|(_, cell)| *cell == '#')
+                    .map(move |(x, _)| (x as i32, height - 1 - row as i32))
+            })
+            .collect()
+    }
+
+    fn rects(cells: &HashSet<(i32, i32)>) -> Vec<(i32, i32, i32, i32)> {
+        let mut rects = Vec::from_iter(
+            merge_cells(cells)
+                .into_iter()
+                .map(|rect| (rect.left, rect.right, rect.bottom, rect.top)),
+        );
+        rects.sort();
+        rects
+    }
+
+    #[test]
+    fn no_cells_give_no_rects() {
+        assert!(merge_cells(&HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn joins_a_row_into_one_plate() {
+        assert_eq!(rects(&cells(&["####"])), vec![(0, 3, 0, 0)]);
+    }
+
+    #[test]
+    fn splits_a_row_at_gaps() {
+        assert_eq!(rects(&cells(&["##.#"])), vec![(0, 1, 0, 0), (3, 3, 0, 0)]);
+    }
+
+    #[test]
+    fn stacks_plates_of_the_same_width() {
+        assert_eq!(rects(&cells(&["###", "###", "###"])), vec![(0, 2, 0, 2)]);
+    }
+
+    #[test]
+    fn keeps_plates_of_different_widths_apart() {
+        assert_eq!(
+            rects(&cells(&["##.", "###"])),
+            vec![(0, 1, 1, 1), (0, 2, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn does_not_stack_plates_across_empty_rows() {
+        assert_eq!(
+            rects(&cells(&["##", "..", "##"])),
+            vec![(0, 1, 0, 0), (0, 1, 2, 2)]
+        );
+    }
+
+    #[test]
+    fn covers_every_cell_exactly_once() {
+        let cells = cells(&["#..##", "####.", "##.##", "#####"]);
+        let mut covered = Vec::new();
+        for (left, right, bottom, top) in rects(&cells) {
+            for y in bottom..=top {
+                for x in left..=right {
+                    covered.push((x, y));
+                }
+            }
+        }
+        assert_eq!(covered.len(), cells.len());
+        assert_eq!(HashSet::from_iter(covered), cells);
+    }
+}
@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use bevy_ecs_ldtk::{ldtk::TilesetRectangle, prelude::*};
+use std::fmt;
+
+#[derive(Debug)]
+pub enum FieldProblem {
+    Missing,
+    WrongType,
+    Invalid(String),
+}
+
+/// Describes which field of which LDtk entity could not be parsed and why.
+#[derive(Debug)]
+pub struct EntityError {
+    /// The level the entity was placed in, empty until the level is known.
+    pub level: String,
+    pub entity: String,
+    pub identifier: String,
+    pub field: String,
+    pub problem: FieldProblem,
+}
+
+impl fmt::Display for EntityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} entity {}", self.identifier, self.entity)?;
+        if !self.level.is_empty() {
+            write!(f, " in level {}", self.level)?;
+        }
+        write!(f, ": field \"{}\" ", self.field)?;
+        match &self.problem {
+            FieldProblem::Missing => write!(f, "is missing"),
+            FieldProblem::WrongType => write!(f, "has an unexpected type"),
+            FieldProblem::Invalid(reason) => write!(f, "is invalid: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for EntityError {}
+
+/// Builds a component from an LDtk entity, falling back to its default when the entity is
+/// broken. Broken entities are reported and removed by the map's entity validation, so the
+/// default is only ever a placeholder until then.
+pub fn from_entity_or_default<T>(entity_instance: &EntityInstance) -> T
+where
+    T: for<'a> TryFrom<&'a EntityInstance> + Default,
+{
+    T::try_from(entity_instance).unwrap_or_default()
+}
+
+/// Typed access to the field instances of an LDtk entity.
+pub struct Fields<'a> {
+    entity_instance: &'a EntityInstance,
+}
+
+impl<'a> Fields<'a> {
+    pub fn new(entity_instance: &'a EntityInstance) -> Self {
+        Self { entity_instance }
+    }
+
+    pub fn error(&self, field: &str, problem: FieldProblem) -> EntityError {
+        EntityError {
+            level: String::new(),
+            entity: self.entity_instance.iid.clone(),
+            identifier: self.entity_instance.identifier.clone(),
+            field: field.to_string(),
+            problem,
+        }
+    }
+
+    fn value(&self, field: &str) -> Option<&'a FieldValue> {
+        self.entity_instance
+            .field_instances
+            .iter()
+            .find(|instance| instance.identifier == field)
+            .map(|instance| &instance.value)
+    }
+
+    fn required<T>(
+        &self,
+        field: &str,
+        value: Result<Option<T>, EntityError>,
+    ) -> Result<T, EntityError> {
+        value?.ok_or_else(|| self.error(field, FieldProblem::Missing))
+    }
+
+    /// Reads a string or enum field, treating a missing or null field as absent.
+    pub fn optional_string(&self, field: &str) -> Result<Option<String>, EntityError> {
+        match self.value(field) {
+            None | Some(FieldValue::String(None)) | Some(FieldValue::Enum(None)) => Ok(None),
+            Some(FieldValue::String(Some(value))) | Some(FieldValue::Enum(Some(value))) => {
+                Ok(Some(value.clone()))
+            }
+            Some(_) => Err(self.error(field, FieldProblem::WrongType)),
+        }
+    }
+
+    pub fn string(&self, field: &str) -> Result<String, EntityError> {
+        self.required(field, self.optional_string(field))
+    }
+
+    pub fn optional_int(&self, field: &str) -> Result<Option<i32>, EntityError> {
+        match self.value(field) {
+            None | Some(FieldValue::Int(None)) => Ok(None),
+            Some(FieldValue::Int(Some(value))) => Ok(Some(*value)),
+            Some(_) => Err(self.error(field, FieldProblem::WrongType)),
+        }
+    }
+
+    pub fn optional_bool(&self, field: &str) -> Result<Option<bool>, EntityError> {
+        match self.value(field) {
+            None => Ok(None),
+            Some(FieldValue::Bool(value)) => Ok(Some(*value)),
+            Some(_) => Err(self.error(field, FieldProblem::WrongType)),
+        }
+    }
+
+    /// Reads a tile field, the rectangle of a tileset picked in the editor.
+    pub fn optional_tile(&self, field: &str) -> Result<Option<TilesetRectangle>, EntityError> {
+        match self.value(field) {
+            None | Some(FieldValue::Tile(None)) => Ok(None),
+            Some(FieldValue::Tile(Some(value))) => Ok(Some(value.clone())),
+            Some(_) => Err(self.error(field, FieldProblem::WrongType)),
+        }
+    }
+
+    pub fn optional_float(&self, field: &str) -> Result<Option<f32>, EntityError> {
+        match self.value(field) {
+            None | Some(FieldValue::Float(None)) | Some(FieldValue::Int(None)) => Ok(None),
+            Some(FieldValue::Float(Some(value))) => Ok(Some(*value)),
+            Some(FieldValue::Int(Some(value))) => Ok(Some(*value as f32)),
+            Some(_) => Err(self.error(field, FieldProblem::WrongType)),
+        }
+    }
+
+    pub fn float(&self, field: &str) -> Result<f32, EntityError> {
+        self.required(field, self.optional_float(field))
+    }
+
+    pub fn color(&self, field: &str) -> Result<Color, EntityError> {
+        match self.value(field) {
+            None => Err(self.error(field, FieldProblem::Missing)),
+            Some(FieldValue::Color(value)) => Ok(*value),
+            Some(_) => Err(self.error(field, FieldProblem::WrongType)),
+        }
+    }
+
+    /// Reads a color array field, treating a missing field as empty.
+    pub fn colors(&self, field: &str) -> Result<Vec<Color>, EntityError> {
+        match self.value(field) {
+            None => Ok(Vec::new()),
+            Some(FieldValue::Colors(values)) => Ok(values.clone()),
+            Some(_) => Err(self.error(field, FieldProblem::WrongType)),
+        }
+    }
+
+    /// Reads a string array field, skipping null entries and treating a missing field as empty.
+    pub fn strings(&self, field: &str) -> Result<Vec<String>, EntityError> {
+        match self.value(field) {
+            None => Ok(Vec::new()),
+            Some(FieldValue::Strings(values)) => Ok(values.iter().flatten().cloned().collect()),
+            Some(_) => Err(self.error(field, FieldProblem::WrongType)),
+        }
+    }
+}
@@ -1,20 +1,39 @@
 use crate::riddles::RiddleInfo;
 use crate::GameState;
-use bevy::{prelude::*, text::Text2dBounds, utils::HashMap};
+use bevy::{prelude::*, text::Text2dBounds};
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
+pub use boxes::{box_image_tile, clue_image_name, BoxImage, ClueImage, ClueText};
+use boxes::{BoxBody, BoxTile, BoxesPlugin};
+pub use clue_regions::ClueRegion;
+use clue_regions::ClueRegionsPlugin;
+use colliders::{CollidersPlugin, Solid};
+pub use fields::{from_entity_or_default, EntityError, FieldProblem, Fields};
+
+mod boxes;
+mod clue_regions;
+mod colliders;
+mod fields;
 
 pub struct MapPlugin;
 
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
-        app.add_startup_system(map_setup_system)
-            .insert_resource(CurrentLevel::from(STARTING_LEVEL))
+        app.add_plugin(CollidersPlugin)
+            .add_plugin(BoxesPlugin)
+            .add_plugin(ClueRegionsPlugin)
+            .add_startup_system(map_setup_system)
+            .insert_resource(CurrentLevel(STARTING_LEVEL.to_string()))
             .add_system_set(
                 SystemSet::on_enter(GameState::LevelLoading).with_system(level_loading_system),
             )
             .add_system_set(
-                SystemSet::on_update(GameState::LevelLoading).with_system(level_loaded_system),
+                SystemSet::on_enter(GameState::MainMenu).with_system(unload_level_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::LevelLoading)
+                    .with_system(level_loaded_system)
+                    .with_system(validate_entities_system),
             )
             .add_system_set(
                 SystemSet::on_exit(GameState::LevelLoading).with_system(normalize_font_system),
@@ -24,15 +43,18 @@ impl Plugin for MapPlugin {
             .register_ldtk_entity::<LevelBorder>("LevelBorder")
             .register_ldtk_entity::<Door>("Door")
             .register_ldtk_entity::<BoxTile>("Box")
+            .register_ldtk_entity::<CheckpointBundle>("Checkpoint")
             .register_ldtk_entity::<TextSignBundle>("TextSign");
     }
 }
 
-const STARTING_LEVEL: &str = "6c6ef290-5110-11ed-90f2-ab2793fe3460";
+pub const STARTING_LEVEL: &str = "6c6ef290-5110-11ed-90f2-ab2793fe3460";
 const SMALL_TILE_SIZE: f32 = 16.0;
 const LARGE_TILE_SIZE: f32 = 64.0;
 
-pub type CurrentLevel = String;
+/// The iid of the level being played.
+#[derive(Deref, DerefMut)]
+pub struct CurrentLevel(pub String);
 
 fn map_setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn_bundle(LdtkWorldBundle {
@@ -42,14 +64,33 @@ fn map_setup_system(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn level_loading_system(
+    mut commands: Commands,
     current_level: Res<CurrentLevel>,
     mut level_set_info: Query<&mut LevelSet>,
+    levels: Query<Entity, With<Handle<LdtkLevel>>>,
 ) {
     let mut level_set = level_set_info.single_mut();
+    if level_set.iids.contains(current_level.as_str()) {
+        // Restarting the same level: despawn it so the level set spawns it afresh.
+        for level in levels.iter() {
+            commands.entity(level).despawn_recursive();
+        }
+    }
     level_set.iids.clear();
     level_set.iids.insert(current_level.clone());
 }
 
+fn unload_level_system(
+    mut commands: Commands,
+    mut level_set_info: Query<&mut LevelSet>,
+    levels: Query<Entity, With<Handle<LdtkLevel>>>,
+) {
+    level_set_info.single_mut().iids.clear();
+    for level in levels.iter() {
+        commands.entity(level).despawn_recursive();
+    }
+}
+
 fn level_loaded_system(mut state: ResMut<State<GameState>>, mut events: EventReader<LevelEvent>) {
     for event in events.iter() {
         if let LevelEvent::Spawned(_) = event {
@@ -58,6 +99,48 @@ fn level_loaded_system(mut state: ResMut<State<GameState>>, mut events: EventRea
     }
 }
 
+/// Checks that the fields of an LDtk entity can be parsed into its components.
+pub fn validate_entity(entity_instance: &EntityInstance) -> Result<(), EntityError> {
+    match entity_instance.identifier.as_ref() {
+        "Door" => RiddleInfo::try_from(entity_instance).map(drop),
+        "TextSign" => TextSign::try_from(entity_instance).map(drop),
+        "Box" => BoxBody::try_from(entity_instance)
+            .and(BoxImage::try_from(entity_instance))
+            .and(ClueImage::try_from(entity_instance))
+            .and(ClueText::try_from(entity_instance))
+            .map(drop),
+        _ => Ok(()),
+    }
+}
+
+fn validate_entities_system(
+    mut commands: Commands,
+    mut events: EventReader<LevelEvent>,
+    levels: Res<Assets<LdtkLevel>>,
+    level_info: Query<&Handle<LdtkLevel>>,
+    entities: Query<(Entity, &EntityInstance)>,
+) {
+    for event in events.iter() {
+        let level_iid = match event {
+            LevelEvent::Spawned(level_iid) => level_iid,
+            _ => continue,
+        };
+        let level_name = level_info
+            .iter()
+            .filter_map(|handle| levels.get(handle))
+            .find(|level| &level.level.iid == level_iid)
+            .map(|level| level.level.identifier.clone())
+            .unwrap_or_else(|| level_iid.clone());
+        for (entity, entity_instance) in entities.iter() {
+            if let Err(mut error) = validate_entity(entity_instance) {
+                error.level = level_name.clone();
+                warn!("Skipping broken entity: {}", error);
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
 fn center_map(
     levels: Res<Assets<LdtkLevel>>,
     mut map_info: Query<(&Handle<LdtkLevel>, &mut Transform)>,
@@ -75,40 +158,41 @@ pub struct Ground;
 #[derive(Default, Bundle, LdtkEntity)]
 struct GroundTile {
     #[from_entity_instance]
-    #[bundle]
-    collider_bundle: ColliderBundle,
-    ground: Ground,
+    solid: Solid,
 }
 
 #[derive(Default, Bundle, LdtkEntity)]
 struct LevelBorder {
     #[from_entity_instance]
-    #[bundle]
-    collider_bundle: ColliderBundle,
+    solid: Solid,
 }
 
 #[derive(Default, Bundle, LdtkEntity)]
-struct BoxTile {
+struct Door {
     #[sprite_sheet_bundle]
     #[bundle]
     sprite_sheet_bundle: SpriteSheetBundle,
     #[from_entity_instance]
     #[bundle]
     collider_bundle: ColliderBundle,
-    ground: Ground,
+    sensor: Sensor,
+    #[from_entity_instance]
+    riddle_info: RiddleInfo,
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
 }
 
+/// Where the player respawns after falling out of the level, once they have touched it.
+#[derive(Default, Component)]
+pub struct Checkpoint;
+
 #[derive(Default, Bundle, LdtkEntity)]
-struct Door {
-    #[sprite_sheet_bundle]
-    #[bundle]
-    sprite_sheet_bundle: SpriteSheetBundle,
+struct CheckpointBundle {
     #[from_entity_instance]
     #[bundle]
     collider_bundle: ColliderBundle,
     sensor: Sensor,
-    #[from_entity_instance]
-    riddle_info: RiddleInfo,
+    checkpoint: Checkpoint,
 }
 
 #[derive(Default, Bundle)]
@@ -120,11 +204,7 @@ struct ColliderBundle {
 impl From<EntityInstance> for ColliderBundle {
     fn from(entity_instance: EntityInstance) -> Self {
         match entity_instance.identifier.as_ref() {
-            "Ground" | "LevelBorder" => Self {
-                collider: Collider::cuboid(SMALL_TILE_SIZE / 2.0, SMALL_TILE_SIZE / 2.0),
-                rigid_body: RigidBody::Fixed,
-            },
-            "Door" | "Box" => Self {
+            "Door" | "Checkpoint" => Self {
                 collider: Collider::cuboid(LARGE_TILE_SIZE / 2.0, LARGE_TILE_SIZE / 2.0),
                 rigid_body: RigidBody::Fixed,
             },
@@ -142,50 +222,28 @@ struct TextSignBundle {
     #[bundle]
     text_sign: TextSign,
     static_text: StaticText,
+    #[from_entity_instance]
+    entity_instance: EntityInstance,
 }
 
-#[derive(Bundle)]
+#[derive(Default, Bundle)]
 struct TextSign {
     #[bundle]
     text_2d_bundle: Text2dBundle,
 }
 
-impl From<EntityInstance> for TextSign {
-    fn from(entity_instance: EntityInstance) -> Self {
-        let fields = HashMap::from_iter(entity_instance.field_instances.iter().map(|field| {
-            (
-                field.identifier.clone(),
-                match field.value.clone() {
-                    FieldValue::String(Some(value)) => value,
-                    FieldValue::Float(Some(value)) => value.to_string(),
-                    _ => "".to_string(),
-                },
-            )
-        }));
-        Self {
+impl TryFrom<&EntityInstance> for TextSign {
+    type Error = EntityError;
+
+    fn try_from(entity_instance: &EntityInstance) -> Result<Self, Self::Error> {
+        let fields = Fields::new(entity_instance);
+        Ok(Self {
             text_2d_bundle: Text2dBundle {
                 text: Text::from_section(
-                    fields
-                        .get("text")
-                        .expect("Text is expected for a text sign!"),
+                    fields.string("text")?,
                     TextStyle {
-                        font_size: fields
-                            .get("font_size")
-                            .expect("The font size of the text is expected!")
-                            .parse()
-                            .expect("Font size is expected to be a number!"),
-                        color: entity_instance
-                            .field_instances
-                            .iter()
-                            .find(|field| field.identifier == "color")
-                            .map(|field| {
-                                if let FieldValue::Color(color) = field.value.clone() {
-                                    color
-                                } else {
-                                    Color::default()
-                                }
-                            })
-                            .expect("Default color is expected to be set by the editor!"),
+                        font_size: fields.float("font_size")?,
+                        color: fields.color("color")?,
                         ..Default::default()
                     },
                 )
@@ -195,7 +253,13 @@ impl From<EntityInstance> for TextSign {
                 },
                 ..Default::default()
             },
-        }
+        })
+    }
+}
+
+impl From<EntityInstance> for TextSign {
+    fn from(entity_instance: EntityInstance) -> Self {
+        from_entity_or_default(&entity_instance)
     }
 }
 
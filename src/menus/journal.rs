@@ -0,0 +1,197 @@
+use super::{
+    menu_root, menu_text,
+    pause::{start_physics_system, stop_physics_system},
+    MenuScreen,
+};
+use crate::{
+    actions::{Action, Actions, InputBindings},
+    clues::InspectedClues,
+    progress::level_name,
+    riddles::{AnsweredRiddles, RiddleAttempts, RiddleJournal},
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+
+pub struct JournalPlugin;
+
+impl Plugin for JournalPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(JournalPages::default())
+            .add_system_set(
+                SystemSet::on_update(GameState::MapExploring).with_system(open_journal_system),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::Journal)
+                    .with_system(write_journal_system)
+                    .with_system(stop_physics_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Journal)
+                    .with_system(close_journal_system)
+                    .with_system(turn_page_system)
+                    .with_system(show_page_system.after(turn_page_system)),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Journal).with_system(start_physics_system),
+            );
+    }
+}
+
+const HEADING_FONT_SIZE: f32 = 36.0;
+const ENTRY_FONT_SIZE: f32 = 22.0;
+const DETAIL_FONT_SIZE: f32 = 18.0;
+/// How many lines fit on a page without the journal overflowing the window.
+const LINES_PER_PAGE: usize = 14;
+
+/// The lines of the journal, with their font sizes, and the page being read.
+#[derive(Default)]
+struct JournalPages {
+    lines: Vec<(String, f32)>,
+    page: usize,
+}
+
+impl JournalPages {
+    fn page_count(&self) -> usize {
+        self.lines.len().div_ceil(LINES_PER_PAGE).max(1)
+    }
+}
+
+fn open_journal_system(mut actions: ResMut<Actions>, mut state: ResMut<State<GameState>>) {
+    actions.open_screen(Action::OpenJournal, &mut state, GameState::Journal);
+}
+
+fn close_journal_system(mut actions: ResMut<Actions>, mut state: ResMut<State<GameState>>) {
+    for action in [Action::OpenJournal, Action::Cancel] {
+        if actions.just_pressed(action) {
+            actions.consume(action);
+            state.set(GameState::MapExploring).unwrap();
+            return;
+        }
+    }
+}
+
+fn turn_page_system(actions: Res<Actions>, mut pages: ResMut<JournalPages>) {
+    let page_count = pages.page_count();
+    if actions.just_pressed(Action::MoveRight) && pages.page + 1 < page_count {
+        pages.page += 1;
+    } else if actions.just_pressed(Action::MoveLeft) && pages.page > 0 {
+        pages.page -= 1;
+    }
+}
+
+fn write_journal_system(
+    journal: Res<RiddleJournal>,
+    answered_riddles: Res<AnsweredRiddles>,
+    attempts: Res<RiddleAttempts>,
+    inspected_clues: Res<InspectedClues>,
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    mut pages: ResMut<JournalPages>,
+    ldtk_info: Query<&Handle<LdtkAsset>>,
+) {
+    let ldtk = ldtk_info
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_assets.get(handle));
+    let level = |level_iid: &str| {
+        ldtk.map(|ldtk| level_name(ldtk, level_iid))
+            .unwrap_or_default()
+    };
+
+    let mut riddles = Vec::from_iter(journal.iter());
+    riddles.sort_by_key(|(id, _)| *id);
+    let mut clues = Vec::from_iter(inspected_clues.values());
+    clues.sort();
+
+    let mut lines = vec![("Riddles".to_string(), HEADING_FONT_SIZE)];
+    if riddles.is_empty() {
+        lines.push(("No riddles found yet".to_string(), DETAIL_FONT_SIZE));
+    }
+    for (id, entry) in riddles {
+        lines.push((
+            format!("{}: {}", level(&id.level), entry.question),
+            ENTRY_FONT_SIZE,
+        ));
+        let status = if answered_riddles.contains(id) {
+            format!("Solved: {}", entry.answer)
+        } else {
+            format!(
+                "Unsolved, {} wrong attempts",
+                attempts.get(id).copied().unwrap_or_default()
+            )
+        };
+        lines.push((status, DETAIL_FONT_SIZE));
+    }
+
+    lines.push(("Clues".to_string(), HEADING_FONT_SIZE));
+    if clues.is_empty() {
+        lines.push(("No clues inspected yet".to_string(), DETAIL_FONT_SIZE));
+    }
+    for clue in clues {
+        // Clues saved before pictures were named have no image name.
+        let image = match clue.image.as_str() {
+            "" => "a picture",
+            image => image,
+        };
+        let description = match &clue.text {
+            Some(text) => format!("{}: {}, {}", level(&clue.level), image, text),
+            None => format!("{}: {}", level(&clue.level), image),
+        };
+        lines.push((description, ENTRY_FONT_SIZE));
+    }
+
+    // Riddle questions span several lines, so count those towards the page size too.
+    *pages = JournalPages {
+        lines: lines
+            .into_iter()
+            .flat_map(|(text, font_size)| {
+                let mut lines =
+                    Vec::from_iter(text.lines().map(|line| (line.to_string(), font_size)));
+                if lines.is_empty() {
+                    lines.push((String::new(), font_size));
+                }
+                lines
+            })
+            .collect(),
+        page: 0,
+    };
+}
+
+fn show_page_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<InputBindings>,
+    pages: Res<JournalPages>,
+    screens: Query<Entity, With<MenuScreen>>,
+) {
+    if !pages.is_changed() {
+        return;
+    }
+    for screen in screens.iter() {
+        commands.entity(screen).despawn_recursive();
+    }
+    let footer = format!(
+        "Page {}/{}: {} and {} to turn pages, {} to close",
+        pages.page + 1,
+        pages.page_count(),
+        bindings.description(Action::MoveLeft),
+        bindings.description(Action::MoveRight),
+        bindings.description(Action::OpenJournal)
+    );
+
+    commands
+        .spawn_bundle(menu_root())
+        .insert(MenuScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(menu_text(&asset_server, "Journal".to_string(), 60.0));
+            for (text, font_size) in pages
+                .lines
+                .iter()
+                .skip(pages.page * LINES_PER_PAGE)
+                .take(LINES_PER_PAGE)
+            {
+                parent.spawn_bundle(menu_text(&asset_server, text.clone(), *font_size));
+            }
+            parent.spawn_bundle(menu_text(&asset_server, footer, DETAIL_FONT_SIZE));
+        });
+}
@@ -0,0 +1,232 @@
+use super::{menu_root, menu_text, spawn_button, MenuScreen};
+use crate::{
+    actions::{default_bindings, write_bindings, Action, InputBindings},
+    progress::{most_recent_slot, LoadSlot},
+    GameState,
+};
+use bevy::{app::AppExit, prelude::*, window::WindowMode};
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RebindingAction>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::MainMenu).with_system(spawn_main_menu_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::MainMenu).with_system(main_menu_action_system),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::Settings).with_system(spawn_settings_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Settings)
+                    .with_system(settings_action_system)
+                    .with_system(rebind_system),
+            );
+    }
+}
+
+#[derive(Component, Clone, Copy)]
+enum MainMenuAction {
+    NewGame,
+    Continue(usize),
+    Settings,
+    Quit,
+}
+
+#[derive(Component, Clone, Copy)]
+enum SettingsAction {
+    ToggleFullscreen,
+    Rebind(Action),
+    ResetControls,
+    Back,
+}
+
+/// The action waiting for a new key or gamepad button on the settings screen.
+#[derive(Default, Deref, DerefMut)]
+struct RebindingAction(Option<Action>);
+
+fn spawn_main_menu_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(menu_root())
+        .insert(MenuScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(menu_text(&asset_server, "Puzzle up".to_string(), 80.0));
+            spawn_button(parent, &asset_server, "New game", MainMenuAction::NewGame);
+            if let Some(slot) = most_recent_slot() {
+                spawn_button(
+                    parent,
+                    &asset_server,
+                    "Continue",
+                    MainMenuAction::Continue(slot),
+                );
+            }
+            spawn_button(parent, &asset_server, "Settings", MainMenuAction::Settings);
+            spawn_button(parent, &asset_server, "Quit", MainMenuAction::Quit);
+        });
+}
+
+fn main_menu_action_system(
+    mut state: ResMut<State<GameState>>,
+    mut load_slots: EventWriter<LoadSlot>,
+    mut exit: EventWriter<AppExit>,
+    buttons: Query<(&Interaction, &MainMenuAction), Changed<Interaction>>,
+) {
+    for (interaction, action) in buttons.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match *action {
+            MainMenuAction::NewGame => state.set(GameState::ProfileSelecting).unwrap(),
+            MainMenuAction::Continue(slot) => load_slots.send(LoadSlot(slot)),
+            MainMenuAction::Settings => state.set(GameState::Settings).unwrap(),
+            MainMenuAction::Quit => exit.send(AppExit),
+        }
+        return;
+    }
+}
+
+fn spawn_settings(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    bindings: &InputBindings,
+    rebinding: &RebindingAction,
+) {
+    commands
+        .spawn_bundle(menu_root())
+        .insert(MenuScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(menu_text(asset_server, "Settings".to_string(), 60.0));
+            spawn_button(
+                parent,
+                asset_server,
+                "Toggle fullscreen",
+                SettingsAction::ToggleFullscreen,
+            );
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        flex_wrap: FlexWrap::Wrap,
+                        justify_content: JustifyContent::Center,
+                        size: Size::new(Val::Percent(80.0), Val::Auto),
+                        ..Default::default()
+                    },
+                    color: UiColor::from(Color::NONE),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    for action in Action::ALL {
+                        let inputs = if **rebinding == Some(action) {
+                            "press a key or button...".to_string()
+                        } else {
+                            bindings.description(action)
+                        };
+                        spawn_button(
+                            parent,
+                            asset_server,
+                            &format!("{:?}: {}", action, inputs),
+                            SettingsAction::Rebind(action),
+                        );
+                    }
+                });
+            spawn_button(
+                parent,
+                asset_server,
+                "Reset controls",
+                SettingsAction::ResetControls,
+            );
+            spawn_button(parent, asset_server, "Back", SettingsAction::Back);
+        });
+}
+
+fn respawn_settings(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    bindings: &InputBindings,
+    rebinding: &RebindingAction,
+    menus: &Query<Entity, With<MenuScreen>>,
+) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+    spawn_settings(commands, asset_server, bindings, rebinding);
+}
+
+fn spawn_settings_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<InputBindings>,
+    mut rebinding: ResMut<RebindingAction>,
+) {
+    **rebinding = None;
+    spawn_settings(&mut commands, &asset_server, &bindings, &rebinding);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn settings_action_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut state: ResMut<State<GameState>>,
+    mut windows: ResMut<Windows>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<RebindingAction>,
+    buttons: Query<(&Interaction, &SettingsAction), Changed<Interaction>>,
+    menus: Query<Entity, With<MenuScreen>>,
+) {
+    for (interaction, action) in buttons.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        match *action {
+            SettingsAction::ToggleFullscreen => {
+                if let Some(window) = windows.get_primary_mut() {
+                    window.set_mode(match window.mode() {
+                        WindowMode::Windowed => WindowMode::BorderlessFullscreen,
+                        _ => WindowMode::Windowed,
+                    });
+                }
+            }
+            SettingsAction::Rebind(action) => {
+                **rebinding = Some(action);
+                respawn_settings(&mut commands, &asset_server, &bindings, &rebinding, &menus);
+            }
+            SettingsAction::ResetControls => {
+                *bindings = default_bindings();
+                write_bindings(&bindings);
+                **rebinding = None;
+                respawn_settings(&mut commands, &asset_server, &bindings, &rebinding, &menus);
+            }
+            SettingsAction::Back => state.set(GameState::MainMenu).unwrap(),
+        }
+        return;
+    }
+}
+
+/// Replaces the keyboard or gamepad part of the binding being changed with the next input.
+fn rebind_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<RebindingAction>,
+    menus: Query<Entity, With<MenuScreen>>,
+) {
+    let action = match **rebinding {
+        Some(action) => action,
+        None => return,
+    };
+    let binding = bindings.entry(action).or_default();
+    if let Some(key) = keyboard_input.get_just_pressed().next() {
+        binding.keys = vec![*key];
+    } else if let Some(button) = gamepad_input.get_just_pressed().next() {
+        binding.buttons = vec![button.button_type];
+    } else {
+        return;
+    }
+    write_bindings(&bindings);
+    **rebinding = None;
+    respawn_settings(&mut commands, &asset_server, &bindings, &rebinding, &menus);
+}
@@ -0,0 +1,114 @@
+use crate::GameState;
+use bevy::prelude::*;
+use journal::JournalPlugin;
+use main_menu::MainMenuPlugin;
+use pause::PausePlugin;
+
+mod journal;
+mod main_menu;
+mod pause;
+
+pub struct MenusPlugin;
+
+impl Plugin for MenusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(MainMenuPlugin)
+            .add_plugin(PausePlugin)
+            .add_plugin(JournalPlugin)
+            .add_system(button_color_system)
+            .add_system_set(
+                SystemSet::on_exit(GameState::MainMenu).with_system(despawn_menu_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Settings).with_system(despawn_menu_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::ProfileSelecting).with_system(despawn_menu_system),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Paused).with_system(despawn_menu_system))
+            .add_system_set(
+                SystemSet::on_exit(GameState::Journal).with_system(despawn_menu_system),
+            );
+    }
+}
+
+pub const FONT: &str = "fonts/MontserratAlternates-MediumItalic.ttf";
+const BACKGROUND_COLOR: Color = Color::rgb(0.5, 0.5, 0.85);
+const BUTTON_COLOR: Color = Color::rgb(0.3, 0.3, 0.6);
+const HOVERED_BUTTON_COLOR: Color = Color::rgb(0.4, 0.4, 0.75);
+
+/// Marks the root node of a menu screen, which is despawned when its state is left.
+#[derive(Component)]
+pub struct MenuScreen;
+
+pub fn menu_root() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::ColumnReverse,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            ..Default::default()
+        },
+        color: UiColor::from(BACKGROUND_COLOR),
+        ..Default::default()
+    }
+}
+
+pub fn menu_text(asset_server: &Res<AssetServer>, value: String, font_size: f32) -> TextBundle {
+    TextBundle {
+        text: Text::from_section(
+            value,
+            TextStyle {
+                font: asset_server.load(FONT),
+                font_size,
+                color: Color::WHITE,
+            },
+        ),
+        ..Default::default()
+    }
+}
+
+pub fn menu_button() -> ButtonBundle {
+    ButtonBundle {
+        style: Style {
+            margin: UiRect::all(Val::Px(8.0)),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..Default::default()
+        },
+        color: UiColor::from(BUTTON_COLOR),
+        ..Default::default()
+    }
+}
+
+pub fn spawn_button(
+    parent: &mut ChildBuilder,
+    asset_server: &Res<AssetServer>,
+    label: &str,
+    action: impl Component,
+) {
+    parent
+        .spawn_bundle(menu_button())
+        .insert(action)
+        .with_children(|parent| {
+            parent.spawn_bundle(menu_text(asset_server, label.to_string(), 30.0));
+        });
+}
+
+#[allow(clippy::type_complexity)]
+fn button_color_system(
+    mut buttons: Query<(&Interaction, &mut UiColor), (Changed<Interaction>, With<Button>)>,
+) {
+    for (interaction, mut color) in buttons.iter_mut() {
+        *color = UiColor::from(match interaction {
+            Interaction::Hovered => HOVERED_BUTTON_COLOR,
+            _ => BUTTON_COLOR,
+        });
+    }
+}
+
+fn despawn_menu_system(mut commands: Commands, menus: Query<Entity, With<MenuScreen>>) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
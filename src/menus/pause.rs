@@ -0,0 +1,95 @@
+use super::{menu_root, menu_text, spawn_button, MenuScreen};
+use crate::{
+    actions::{Action, Actions},
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_update(GameState::MapExploring).with_system(pause_system))
+            .add_system_set(
+                SystemSet::on_enter(GameState::Paused)
+                    .with_system(spawn_pause_menu_system)
+                    .with_system(stop_physics_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Paused)
+                    .with_system(pause_action_system)
+                    .with_system(resume_system),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Paused).with_system(start_physics_system),
+            );
+    }
+}
+
+const PAUSE_BACKGROUND_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.6);
+
+#[derive(Component, Clone, Copy)]
+enum PauseAction {
+    Resume,
+    RestartLevel,
+    MainMenu,
+}
+
+fn pause_system(mut actions: ResMut<Actions>, mut state: ResMut<State<GameState>>) {
+    actions.open_screen(Action::Cancel, &mut state, GameState::Paused);
+}
+
+fn resume_system(mut actions: ResMut<Actions>, mut state: ResMut<State<GameState>>) {
+    if actions.just_pressed(Action::Cancel) {
+        actions.consume(Action::Cancel);
+        state.set(GameState::MapExploring).unwrap();
+    }
+}
+
+pub(super) fn stop_physics_system(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = false;
+}
+
+pub(super) fn start_physics_system(mut rapier_config: ResMut<RapierConfiguration>) {
+    rapier_config.physics_pipeline_active = true;
+}
+
+fn spawn_pause_menu_system(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(NodeBundle {
+            color: UiColor::from(PAUSE_BACKGROUND_COLOR),
+            ..menu_root()
+        })
+        .insert(MenuScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(menu_text(&asset_server, "Paused".to_string(), 60.0));
+            spawn_button(parent, &asset_server, "Resume", PauseAction::Resume);
+            spawn_button(
+                parent,
+                &asset_server,
+                "Restart level",
+                PauseAction::RestartLevel,
+            );
+            spawn_button(parent, &asset_server, "Main menu", PauseAction::MainMenu);
+        });
+}
+
+fn pause_action_system(
+    mut state: ResMut<State<GameState>>,
+    buttons: Query<(&Interaction, &PauseAction), Changed<Interaction>>,
+) {
+    for (interaction, action) in buttons.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        state
+            .set(match *action {
+                PauseAction::Resume => GameState::MapExploring,
+                PauseAction::RestartLevel => GameState::LevelLoading,
+                PauseAction::MainMenu => GameState::MainMenu,
+            })
+            .unwrap();
+        return;
+    }
+}
@@ -0,0 +1,189 @@
+use super::{Player, PlayerMovement};
+use crate::animation::{AnimationGraph, MotionState, SpriteAnimator};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::EntityCommands,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_rapier2d::prelude::*;
+use serde::Deserialize;
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<PlayerConfig>()
+            .init_asset_loader::<PlayerConfigLoader>()
+            .add_startup_system(load_player_config_system)
+            .add_system(reload_player_config_system)
+            .add_system(configure_player_system);
+    }
+}
+
+const PLAYER_CONFIG: &str = "player/tuning.player.ron";
+
+/// Describes the player's physics, collider, sprite sheet and animations.
+#[derive(Clone, Deserialize, TypeUuid)]
+#[uuid = "3c4f9a62-8d1e-4b7a-9f25-6e0d2a51c8b3"]
+pub struct PlayerConfig {
+    pub gravity: f32,
+    pub movement: PlayerMovement,
+    pub collider_width: f32,
+    pub collider_height: f32,
+    pub sprite_sheet: SpriteSheetLayout,
+    pub animation: AnimationGraph,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct SpriteSheetLayout {
+    pub path: String,
+    pub tile_width: f32,
+    pub tile_height: f32,
+    pub columns: usize,
+    pub rows: usize,
+    pub index: usize,
+}
+
+/// The config shipped with the game, used until the asset is loaded.
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        ron::from_str(include_str!("../../assets/player/tuning.player.ron"))
+            .expect("The bundled player config is expected to be valid!")
+    }
+}
+
+#[derive(Default)]
+struct PlayerConfigLoader;
+
+impl AssetLoader for PlayerConfigLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let config = ron::de::from_bytes::<PlayerConfig>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(config));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["player.ron"]
+    }
+}
+
+/// The config the player is currently built from, together with its texture atlas.
+struct ActivePlayerConfig {
+    handle: Handle<PlayerConfig>,
+    config: PlayerConfig,
+    texture_atlas: Handle<TextureAtlas>,
+}
+
+fn texture_atlas(
+    asset_server: &AssetServer,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    layout: &SpriteSheetLayout,
+) -> Handle<TextureAtlas> {
+    texture_atlases.add(TextureAtlas::from_grid(
+        asset_server.load(&layout.path),
+        Vec2::new(layout.tile_width, layout.tile_height),
+        layout.columns,
+        layout.rows,
+    ))
+}
+
+fn apply_config(
+    config: &PlayerConfig,
+    texture_atlas: &Handle<TextureAtlas>,
+    player: &mut EntityCommands,
+) {
+    player
+        .insert(Collider::cuboid(
+            config.collider_width / 2.0,
+            config.collider_height / 2.0,
+        ))
+        .insert(texture_atlas.clone())
+        .insert(TextureAtlasSprite::new(config.sprite_sheet.index))
+        .insert(SpriteAnimator::new(config.animation.clone()))
+        .insert(MotionState::default());
+}
+
+fn load_player_config_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+) {
+    let config = PlayerConfig::default();
+    rapier_config.gravity = Vec2::new(0.0, config.gravity);
+    commands.insert_resource(config.movement.clone());
+    commands.insert_resource(ActivePlayerConfig {
+        handle: asset_server.load(PLAYER_CONFIG),
+        texture_atlas: texture_atlas(&asset_server, &mut texture_atlases, &config.sprite_sheet),
+        config,
+    });
+}
+
+/// Rebuilds the player whenever the config asset is loaded or changed on disk.
+#[allow(clippy::too_many_arguments)]
+fn reload_player_config_system(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<PlayerConfig>>,
+    configs: Res<Assets<PlayerConfig>>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut active_config: ResMut<ActivePlayerConfig>,
+    mut movement: ResMut<PlayerMovement>,
+    mut rapier_config: ResMut<RapierConfiguration>,
+    players: Query<Entity, With<Player>>,
+) {
+    let changed = events.iter().any(|event| match event {
+        AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+            *handle == active_config.handle
+        }
+        AssetEvent::Removed { .. } => false,
+    });
+    if !changed {
+        return;
+    }
+    let config = match configs.get(&active_config.handle) {
+        Some(config) => config.clone(),
+        None => return,
+    };
+    if let Err(error) = config.animation.validate() {
+        warn!(
+            "Ignoring {} since its animation is broken: {}",
+            PLAYER_CONFIG, error
+        );
+        return;
+    }
+    rapier_config.gravity = Vec2::new(0.0, config.gravity);
+    *movement = config.movement.clone();
+    active_config.texture_atlas =
+        texture_atlas(&asset_server, &mut texture_atlases, &config.sprite_sheet);
+    active_config.config = config;
+    for player in players.iter() {
+        apply_config(
+            &active_config.config,
+            &active_config.texture_atlas,
+            &mut commands.entity(player),
+        );
+    }
+}
+
+fn configure_player_system(
+    mut commands: Commands,
+    active_config: Res<ActivePlayerConfig>,
+    players: Query<Entity, Added<Player>>,
+) {
+    for player in players.iter() {
+        apply_config(
+            &active_config.config,
+            &active_config.texture_atlas,
+            &mut commands.entity(player),
+        );
+    }
+}
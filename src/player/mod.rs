@@ -1,16 +1,27 @@
-use crate::{map::Ground, GameState};
-use animations::{AnimationInfo, AnimationType, AnimationsPlugin};
-use bevy::{prelude::*, utils::HashMap};
+use crate::{
+    actions::{Action, Actions},
+    animation::{AnimateSprites, MotionState},
+    map::Ground,
+    GameState,
+};
+use bevy::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
+use config::ConfigPlugin;
+pub use respawn::reset_respawn_point_system;
+use respawn::RespawnPlugin;
+use serde::Deserialize;
 
-mod animations;
+mod config;
+mod respawn;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugin(AnimationsPlugin)
+        app.add_plugin(ConfigPlugin)
+            .add_plugin(RespawnPlugin)
+            .add_system(motion_state_system.before(AnimateSprites))
             .add_system_set(
                 SystemSet::on_update(GameState::MapExploring).with_system(player_movement_system),
             )
@@ -18,17 +29,33 @@ impl Plugin for PlayerPlugin {
     }
 }
 
-const PLAYER_WIDTH: f32 = 60.0;
-const PLAYER_HEIGHT: f32 = 110.0;
-const JUMP_POWER: f32 = 250.0;
-const RUN_POWER: f32 = 100.0;
+/// Tunables of the player's movement, in pixels and seconds.
+#[derive(Clone, Deserialize)]
+pub struct PlayerMovement {
+    pub run_speed: f32,
+    pub jump_speed: f32,
+    /// The factor the upward speed is multiplied by when the jump is released early.
+    pub jump_cut: f32,
+    /// How long after walking off a ledge the player can still jump.
+    pub coyote_time: f32,
+    /// How long a jump pressed in the air is remembered before landing.
+    pub jump_buffer: f32,
+    /// The smallest vertical component of a contact normal that still counts as ground.
+    pub max_slope_cos: f32,
+}
 
 #[derive(Default, Component)]
 pub struct Player;
 
+#[derive(Default, Component)]
+struct JumpState {
+    since_grounded: f32,
+    since_jump_pressed: Option<f32>,
+    jumping: bool,
+}
+
 #[derive(Default, Bundle, LdtkEntity)]
 struct PlayerBundle {
-    #[sprite_sheet_bundle("player/player_tilesheet.png", 80.0, 110.0, 9, 3, 0.0, 0.0, 24)]
     #[bundle]
     sprite_sheet_bundle: SpriteSheetBundle,
     #[from_entity_instance]
@@ -36,6 +63,7 @@ struct PlayerBundle {
     collider_bundle: ColliderBundle,
     velocity: Velocity,
     player: Player,
+    jump_state: JumpState,
 }
 
 #[derive(Default, Bundle)]
@@ -45,13 +73,11 @@ struct ColliderBundle {
     locked_axes: LockedAxes,
     friction: Friction,
     active_events: ActiveEvents,
-    animation_info: AnimationInfo,
 }
 
 impl From<EntityInstance> for ColliderBundle {
     fn from(_: EntityInstance) -> Self {
         Self {
-            collider: Collider::cuboid(PLAYER_WIDTH / 2.0, PLAYER_HEIGHT / 2.0),
             rigid_body: RigidBody::Dynamic,
             locked_axes: LockedAxes::ROTATION_LOCKED,
             friction: Friction {
@@ -59,56 +85,111 @@ impl From<EntityInstance> for ColliderBundle {
                 combine_rule: CoefficientCombineRule::Min,
             },
             active_events: ActiveEvents::COLLISION_EVENTS,
-            animation_info: AnimationInfo::new(
-                HashMap::from_iter([
-                    (AnimationType::IDLE, vec![0]),
-                    (AnimationType::RUN, vec![9, 10]),
-                    (AnimationType::JUMP, vec![1]),
-                    (AnimationType::FALL, vec![2]),
-                ]),
-                AnimationType::IDLE,
-                Timer::from_seconds(0.2, true),
-            ),
+            ..Default::default()
         }
     }
 }
 
+fn standing_on(
+    rapier_context: &RapierContext,
+    player: Entity,
+    tile: Entity,
+    max_slope_cos: f32,
+) -> bool {
+    let contact_pair = match rapier_context.contact_pair(player, tile) {
+        Some(contact_pair) => contact_pair,
+        None => return false,
+    };
+    let standing = contact_pair.manifolds().any(|manifold| {
+        // The normal points from the first body to the second, so flip it to point at the player.
+        let normal = if manifold.rigid_body1() == Some(player) {
+            -manifold.normal()
+        } else {
+            manifold.normal()
+        };
+        manifold.num_points() > 0 && normal.y >= max_slope_cos
+    });
+    standing
+}
+
+/// Tells the player's animator whether the player stands on the ground and how it moves.
+fn motion_state_system(
+    state: Res<State<GameState>>,
+    rapier_context: Res<RapierContext>,
+    movement: Res<PlayerMovement>,
+    mut player_info: Query<(Entity, &Velocity, &mut MotionState), With<Player>>,
+    ground_info: Query<Entity, With<Ground>>,
+) {
+    if !state.current().is_playing() {
+        return;
+    }
+    for (player, velocity, mut motion) in player_info.iter_mut() {
+        motion.grounded = ground_info
+            .iter()
+            .any(|ground| standing_on(&rapier_context, player, ground, movement.max_slope_cos));
+        motion.velocity = velocity.linvel;
+    }
+}
+
 fn player_movement_system(
-    keyboard_input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    actions: Res<Actions>,
+    movement: Res<PlayerMovement>,
     rapier_context: Res<RapierContext>,
-    mut player_info: Query<(Entity, &mut Velocity, &mut TextureAtlasSprite), With<Player>>,
+    mut player_info: Query<
+        (
+            Entity,
+            &mut Velocity,
+            &mut TextureAtlasSprite,
+            &mut JumpState,
+        ),
+        With<Player>,
+    >,
     tile_info: Query<Entity, With<Ground>>,
 ) {
-    let (player, mut velocity, mut sprite) = player_info.single_mut();
-    let up = keyboard_input.any_pressed([KeyCode::Up, KeyCode::W]);
-    let left = keyboard_input.any_pressed([KeyCode::Left, KeyCode::A]);
-    let right = keyboard_input.any_pressed([KeyCode::Right, KeyCode::D]);
+    let (player, mut velocity, mut sprite, mut jump) = player_info.single_mut();
+    let delta = time.delta_seconds();
+    let left = actions.pressed(Action::MoveLeft);
+    let right = actions.pressed(Action::MoveRight);
 
     velocity.linvel.x = if left {
         sprite.flip_x = true;
-        -RUN_POWER
+        -movement.run_speed
     } else if right {
         sprite.flip_x = false;
-        RUN_POWER
+        movement.run_speed
     } else {
         0.0
     };
 
-    if up {
-        for tile in tile_info.iter() {
-            if let Some(contact_pair) = rapier_context.contact_pair(player, tile) {
-                for manifold in contact_pair.manifolds() {
-                    let first_entity = manifold
-                        .rigid_body1()
-                        .expect("An entity is expected when collision is detected!");
-                    if (first_entity == player && manifold.normal().y == -1.0)
-                        || manifold.normal().y == 1.0
-                    {
-                        velocity.linvel.y = JUMP_POWER;
-                        return;
-                    }
-                }
-            }
-        }
+    let grounded = tile_info
+        .iter()
+        .any(|tile| standing_on(&rapier_context, player, tile, movement.max_slope_cos));
+    if grounded && (!jump.jumping || velocity.linvel.y <= 0.0) {
+        jump.since_grounded = 0.0;
+        jump.jumping = false;
+    } else {
+        jump.since_grounded += delta;
+    }
+
+    jump.since_jump_pressed = if actions.just_pressed(Action::Jump) {
+        Some(0.0)
+    } else {
+        jump.since_jump_pressed
+            .map(|elapsed| elapsed + delta)
+            .filter(|elapsed| *elapsed <= movement.jump_buffer)
+    };
+
+    if jump.since_jump_pressed.is_some()
+        && !jump.jumping
+        && jump.since_grounded <= movement.coyote_time
+    {
+        velocity.linvel.y = movement.jump_speed;
+        jump.jumping = true;
+        jump.since_jump_pressed = None;
+        jump.since_grounded = f32::INFINITY;
+    } else if jump.jumping && !actions.pressed(Action::Jump) && velocity.linvel.y > 0.0 {
+        velocity.linvel.y *= movement.jump_cut;
+        jump.jumping = false;
     }
 }
@@ -0,0 +1,115 @@
+use super::{JumpState, Player};
+use crate::{
+    animation::{MotionState, SpriteAnimator},
+    map::Checkpoint,
+    GameState,
+};
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RespawnPoint>()
+            .add_system_set(
+                SystemSet::on_exit(GameState::LevelLoading).with_system(reset_respawn_point_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::MapExploring)
+                    .with_system(reach_checkpoint_system)
+                    .with_system(out_of_bounds_system),
+            );
+    }
+}
+
+/// How far past the edges of the level the player may go before being respawned.
+const OUT_OF_BOUNDS_MARGIN: f32 = 64.0;
+
+/// Where the player respawns in the level's entity layer: the last checkpoint touched, or the
+/// position the level spawned them at.
+#[derive(Default, Deref, DerefMut)]
+pub struct RespawnPoint(Option<Vec2>);
+
+/// Starts every level with the player's spawn as respawn point. Runs before a saved position
+/// is restored, so falling always brings the player back to a place the level designed.
+pub fn reset_respawn_point_system(
+    mut respawn_point: ResMut<RespawnPoint>,
+    player_info: Query<&Transform, With<Player>>,
+) {
+    **respawn_point = player_info
+        .get_single()
+        .ok()
+        .map(|transform| transform.translation.truncate());
+}
+
+fn reach_checkpoint_system(
+    rapier_context: Res<RapierContext>,
+    mut respawn_point: ResMut<RespawnPoint>,
+    player_info: Query<Entity, With<Player>>,
+    checkpoints: Query<(Entity, &Transform), With<Checkpoint>>,
+) {
+    let player = player_info.single();
+    for (checkpoint, transform) in checkpoints.iter() {
+        let position = transform.translation.truncate();
+        if rapier_context.intersection_pair(player, checkpoint) == Some(true)
+            && **respawn_point != Some(position)
+        {
+            **respawn_point = Some(position);
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn out_of_bounds_system(
+    respawn_point: Res<RespawnPoint>,
+    levels: Res<Assets<LdtkLevel>>,
+    level_info: Query<&Handle<LdtkLevel>>,
+    mut player_info: Query<
+        (
+            &GlobalTransform,
+            &mut Transform,
+            &mut Velocity,
+            &mut JumpState,
+            &mut MotionState,
+            &mut SpriteAnimator,
+        ),
+        With<Player>,
+    >,
+) {
+    let level = match level_info
+        .get_single()
+        .ok()
+        .and_then(|handle| levels.get(handle))
+    {
+        Some(level) => level,
+        None => return,
+    };
+    // The animator is only added to the player once its config is applied.
+    let (global_transform, mut transform, mut velocity, mut jump, mut motion, mut animator) =
+        match player_info.get_single_mut() {
+            Ok(player) => player,
+            Err(_) => return,
+        };
+    // The level is centered on the origin by the map's center_map system.
+    let half_level = Vec2::new(level.level.px_wid as f32, level.level.px_hei as f32) / 2.0;
+    let position = global_transform.translation().truncate();
+    if position
+        .abs()
+        .cmple(half_level + OUT_OF_BOUNDS_MARGIN)
+        .all()
+    {
+        return;
+    }
+    let spawn = match **respawn_point {
+        Some(spawn) => spawn,
+        None => return,
+    };
+    transform.translation.x = spawn.x;
+    transform.translation.y = spawn.y;
+    *velocity = Velocity::zero();
+    *jump = JumpState::default();
+    *motion = MotionState::default();
+    animator.reset();
+}
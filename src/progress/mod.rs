@@ -0,0 +1,350 @@
+use crate::{
+    clues::{ClueNote, InspectedClues},
+    files::write_ron,
+    map::{CurrentLevel, STARTING_LEVEL},
+    player::{reset_respawn_point_system, Player},
+    riddles::{
+        AnsweredRiddles, HintsUsed, JournalEntry, LegacyAnsweredRiddles, RiddleAttempts, RiddleId,
+        RiddleJournal, RiddleLockouts,
+    },
+    GameState,
+};
+use bevy::{prelude::*, time::Stopwatch};
+use bevy_ecs_ldtk::prelude::*;
+use profiles::ProfilesPlugin;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+mod profiles;
+
+pub struct ProgressPlugin;
+
+impl Plugin for ProgressPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(ProfilesPlugin)
+            .init_resource::<PlayTime>()
+            .init_resource::<SavedSpawn>()
+            .init_resource::<ActiveSlot>()
+            .init_resource::<ProfileName>()
+            .add_event::<LoadSlot>()
+            .add_system(load_slot_system)
+            .add_system(play_time_system)
+            .add_system(autosave_system)
+            .add_system_set(
+                SystemSet::on_exit(GameState::LevelLoading).with_system(
+                    restore_spawn_system
+                        .label(RestoreSpawn)
+                        .after(reset_respawn_point_system),
+                ),
+            );
+    }
+}
+
+pub const SAVE_SLOTS: usize = 3;
+const SAVE_DIRECTORY: &str = "puzzle-up";
+const LEGACY_SAVE_FILE: &str = "save.ron";
+
+/// How long the loaded save has been played, counting only time spent in a level.
+#[derive(Default, Deref, DerefMut)]
+pub struct PlayTime(Stopwatch);
+
+#[derive(Default, Deref, DerefMut)]
+pub struct ActiveSlot(pub usize);
+
+/// The name the player gave the loaded save.
+#[derive(Default, Deref, DerefMut)]
+struct ProfileName(String);
+
+/// Where the loaded save left the player, applied once its level is spawned.
+#[derive(Default, Deref, DerefMut)]
+struct SavedSpawn(Option<Vec2>);
+
+/// Labels the system that moves the player to where the loaded save left them, so systems
+/// that start from the player's position can run after it.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RestoreSpawn;
+
+/// Loads the progress stored in a save slot and starts playing from it.
+pub struct LoadSlot(pub usize);
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+pub struct SaveData {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub solved_riddles: Vec<RiddleId>,
+    /// Riddles solved by older saves, keyed by their question text.
+    #[serde(default, rename = "answered_riddles")]
+    pub legacy_answered_riddles: Vec<String>,
+    pub current_level: String,
+    pub player_spawn: Option<(f32, f32)>,
+    pub play_time: f32,
+    #[serde(default)]
+    pub level_name: String,
+    #[serde(default)]
+    pub total_riddles: usize,
+    #[serde(default)]
+    pub last_played: u64,
+    #[serde(default)]
+    pub hints_used: Vec<(RiddleId, usize)>,
+    #[serde(default)]
+    pub attempts: Vec<(RiddleId, usize)>,
+    /// The play time at which locked out riddles open up again.
+    #[serde(default)]
+    pub lockouts: Vec<(RiddleId, f32)>,
+    #[serde(default)]
+    pub journal: Vec<(RiddleId, JournalEntry)>,
+    #[serde(default)]
+    pub inspected_clues: Vec<(String, ClueNote)>,
+}
+
+fn save_directory() -> Option<PathBuf> {
+    dirs::data_dir().map(|directory| directory.join(SAVE_DIRECTORY))
+}
+
+fn slot_path(slot: usize) -> Option<PathBuf> {
+    save_directory().map(|directory| directory.join(format!("save_{}.ron", slot)))
+}
+
+fn parse_save(path: PathBuf) -> Option<SaveData> {
+    let contents = fs::read_to_string(&path).ok()?;
+    ron::from_str(&contents)
+        .map_err(|error| warn!("Ignoring malformed save file {:?}: {}", path, error))
+        .ok()
+}
+
+pub fn read_save(slot: usize) -> Option<SaveData> {
+    let save = parse_save(slot_path(slot)?);
+    if save.is_none() && slot == 0 {
+        return parse_save(save_directory()?.join(LEGACY_SAVE_FILE));
+    }
+    save
+}
+
+pub fn write_save(slot: usize, data: &SaveData) {
+    let path = match slot_path(slot) {
+        Some(path) => path,
+        None => {
+            warn!("No data directory is available, progress will not be saved!");
+            return;
+        }
+    };
+    if let Err(error) = write_ron(&path, data) {
+        warn!("Could not save progress to {:?}: {}", path, error);
+    }
+}
+
+pub fn delete_save(slot: usize) {
+    let paths = [
+        slot_path(slot),
+        save_directory()
+            .filter(|_| slot == 0)
+            .map(|directory| directory.join(LEGACY_SAVE_FILE)),
+    ];
+    for path in paths.into_iter().flatten() {
+        if path.exists() {
+            if let Err(error) = fs::remove_file(&path) {
+                warn!("Could not delete save file {:?}: {}", path, error);
+            }
+        }
+    }
+}
+
+pub fn most_recent_slot() -> Option<usize> {
+    (0..SAVE_SLOTS)
+        .filter_map(|slot| read_save(slot).map(|save| (slot, save.last_played)))
+        .max_by_key(|(_, last_played)| *last_played)
+        .map(|(slot, _)| slot)
+}
+
+fn sorted<T: Ord>(items: impl Iterator<Item = T>) -> Vec<T> {
+    let mut items = Vec::from_iter(items);
+    items.sort();
+    items
+}
+
+/// The identifier the designers gave a level, shown instead of its iid.
+pub fn level_name(ldtk: &LdtkAsset, level_iid: &str) -> String {
+    ldtk.project
+        .levels
+        .iter()
+        .find(|level| level.iid == level_iid)
+        .map(|level| level.identifier.clone())
+        .unwrap_or_default()
+}
+
+/// Like `sorted`, for values such as floats that have no total order.
+fn sorted_by_id<T>(items: impl Iterator<Item = (RiddleId, T)>) -> Vec<(RiddleId, T)> {
+    let mut items = Vec::from_iter(items);
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    items
+}
+
+fn level_summary(ldtk: &LdtkAsset, level_iid: &str) -> (String, usize) {
+    let level_name = level_name(ldtk, level_iid);
+    let total_riddles = ldtk
+        .project
+        .levels
+        .iter()
+        .flat_map(|level| level.layer_instances.iter().flatten())
+        .flat_map(|layer| layer.entity_instances.iter())
+        .filter(|entity| entity.identifier == "Door")
+        .count();
+    (level_name, total_riddles)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn load_slot_system(
+    mut events: EventReader<LoadSlot>,
+    mut state: ResMut<State<GameState>>,
+    mut active_slot: ResMut<ActiveSlot>,
+    mut profile_name: ResMut<ProfileName>,
+    mut answered_riddles: ResMut<AnsweredRiddles>,
+    mut legacy_answered_riddles: ResMut<LegacyAnsweredRiddles>,
+    mut hints_used: ResMut<HintsUsed>,
+    mut attempts: ResMut<RiddleAttempts>,
+    mut lockouts: ResMut<RiddleLockouts>,
+    mut journal: ResMut<RiddleJournal>,
+    mut inspected_clues: ResMut<InspectedClues>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut saved_spawn: ResMut<SavedSpawn>,
+    mut play_time: ResMut<PlayTime>,
+) {
+    let slot = match events.iter().last() {
+        Some(LoadSlot(slot)) => *slot,
+        None => return,
+    };
+    let data = read_save(slot).unwrap_or_default();
+    **active_slot = slot;
+    **profile_name = data.name;
+    answered_riddles.clear();
+    answered_riddles.extend(data.solved_riddles);
+    legacy_answered_riddles.clear();
+    legacy_answered_riddles.extend(data.legacy_answered_riddles);
+    hints_used.clear();
+    hints_used.extend(data.hints_used);
+    attempts.clear();
+    attempts.extend(data.attempts);
+    lockouts.clear();
+    lockouts.extend(data.lockouts);
+    journal.clear();
+    journal.extend(data.journal);
+    inspected_clues.clear();
+    inspected_clues.extend(data.inspected_clues);
+    **current_level = if data.current_level.is_empty() {
+        STARTING_LEVEL.to_string()
+    } else {
+        data.current_level
+    };
+    **saved_spawn = data.player_spawn.map(|(x, y)| Vec2::new(x, y));
+    play_time.set_elapsed(Duration::from_secs_f32(data.play_time));
+    state.set(GameState::LevelLoading).unwrap();
+}
+
+fn play_time_system(
+    time: Res<Time>,
+    state: Res<State<GameState>>,
+    mut play_time: ResMut<PlayTime>,
+) {
+    if !state.current().is_playing() {
+        return;
+    }
+    play_time.tick(time.delta());
+}
+
+#[allow(clippy::too_many_arguments)]
+fn autosave_system(
+    state: Res<State<GameState>>,
+    active_slot: Res<ActiveSlot>,
+    profile_name: Res<ProfileName>,
+    answered_riddles: Res<AnsweredRiddles>,
+    legacy_answered_riddles: Res<LegacyAnsweredRiddles>,
+    hints_used: Res<HintsUsed>,
+    attempts: Res<RiddleAttempts>,
+    lockouts: Res<RiddleLockouts>,
+    journal: Res<RiddleJournal>,
+    inspected_clues: Res<InspectedClues>,
+    current_level: Res<CurrentLevel>,
+    saved_spawn: Res<SavedSpawn>,
+    play_time: Res<PlayTime>,
+    ldtk_assets: Res<Assets<LdtkAsset>>,
+    ldtk_info: Query<&Handle<LdtkAsset>>,
+    player_info: Query<&Transform, With<Player>>,
+) {
+    if !state.current().is_playing() {
+        return;
+    }
+    if !answered_riddles.is_changed()
+        && !hints_used.is_changed()
+        && !attempts.is_changed()
+        && !lockouts.is_changed()
+        && !journal.is_changed()
+        && !inspected_clues.is_changed()
+        && !current_level.is_changed()
+    {
+        return;
+    }
+    let player_spawn = if current_level.is_changed() {
+        **saved_spawn
+    } else {
+        player_info
+            .get_single()
+            .ok()
+            .map(|transform| transform.translation.truncate())
+    };
+    let (level_name, total_riddles) = ldtk_info
+        .get_single()
+        .ok()
+        .and_then(|handle| ldtk_assets.get(handle))
+        .map(|ldtk| level_summary(ldtk, &current_level))
+        .unwrap_or_default();
+    write_save(
+        **active_slot,
+        &SaveData {
+            name: profile_name.clone(),
+            solved_riddles: sorted(answered_riddles.iter().cloned()),
+            legacy_answered_riddles: sorted(legacy_answered_riddles.iter().cloned()),
+            current_level: current_level.clone(),
+            player_spawn: player_spawn.map(|spawn| (spawn.x, spawn.y)),
+            play_time: play_time.elapsed_secs(),
+            level_name,
+            total_riddles,
+            last_played: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_secs())
+                .unwrap_or_default(),
+            hints_used: sorted(hints_used.iter().map(|(id, hints)| (id.clone(), *hints))),
+            attempts: sorted(
+                attempts
+                    .iter()
+                    .map(|(id, attempts)| (id.clone(), *attempts)),
+            ),
+            lockouts: sorted_by_id(lockouts.iter().map(|(id, expiry)| (id.clone(), *expiry))),
+            journal: sorted(
+                journal
+                    .iter()
+                    .map(|(id, entry)| (id.clone(), entry.clone())),
+            ),
+            inspected_clues: sorted(
+                inspected_clues
+                    .iter()
+                    .map(|(iid, note)| (iid.clone(), note.clone())),
+            ),
+        },
+    );
+}
+
+fn restore_spawn_system(
+    mut saved_spawn: ResMut<SavedSpawn>,
+    mut player_info: Query<&mut Transform, With<Player>>,
+) {
+    if let Some(spawn) = saved_spawn.take() {
+        let mut transform = player_info.single_mut();
+        transform.translation.x = spawn.x;
+        transform.translation.y = spawn.y;
+    }
+}
@@ -0,0 +1,329 @@
+use super::{delete_save, read_save, write_save, LoadSlot, SaveData, SAVE_SLOTS};
+use crate::{
+    actions::{Action, Actions, InputBindings},
+    menus::{menu_root, menu_text, spawn_button, MenuScreen},
+    GameState,
+};
+use bevy::{prelude::*, utils::HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct ProfilesPlugin;
+
+impl Plugin for ProfilesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NewProfile>()
+            .init_resource::<ConfirmingAction>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::ProfileSelecting)
+                    .with_system(spawn_profile_menu_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::ProfileSelecting)
+                    .with_system(profile_button_system)
+                    .with_system(type_profile_name_system),
+            );
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SlotAction {
+    Play(usize),
+    NewGame(usize),
+    Start,
+    Copy(usize),
+    Delete(usize),
+    Back,
+}
+
+const MAX_NAME_LENGTH: usize = 16;
+
+/// The empty slot a new game is being named for, and the name typed so far.
+#[derive(Default)]
+struct NewProfile {
+    slot: Option<usize>,
+    name: String,
+}
+
+/// The copy or delete that was clicked once and runs when its button is clicked again.
+#[derive(Default, Deref, DerefMut)]
+struct ConfirmingAction(Option<SlotAction>);
+
+#[derive(Component)]
+struct ProfileNameText;
+
+fn time_ago(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default();
+    match now.saturating_sub(timestamp) {
+        0..=59 => "just now".to_string(),
+        seconds @ 60..=3599 => format!("{} min ago", seconds / 60),
+        seconds @ 3600..=86399 => format!("{} h ago", seconds / 3600),
+        seconds => format!("{} days ago", seconds / 86400),
+    }
+}
+
+/// Counts the solved riddles, including legacy questions that no solved door has taken over.
+fn solved_riddles(save: &SaveData) -> usize {
+    let migrated = HashSet::<&String>::from_iter(
+        save.journal
+            .iter()
+            .filter(|(id, _)| save.solved_riddles.contains(id))
+            .map(|(_, entry)| &entry.question),
+    );
+    save.solved_riddles.len()
+        + save
+            .legacy_answered_riddles
+            .iter()
+            .filter(|question| !migrated.contains(question))
+            .count()
+}
+
+fn slot_description(slot: usize, save: &Option<SaveData>) -> String {
+    match save {
+        Some(save) => format!(
+            "Slot {}{}: {} - {}/{} riddles - {} hints - {}",
+            slot + 1,
+            if save.name.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", save.name)
+            },
+            save.level_name,
+            solved_riddles(save),
+            save.total_riddles,
+            save.hints_used
+                .iter()
+                .map(|(_, hints)| hints)
+                .sum::<usize>(),
+            time_ago(save.last_played)
+        ),
+        None => format!("Slot {}: empty", slot + 1),
+    }
+}
+
+fn spawn_profile_menu(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    confirming: Option<SlotAction>,
+) {
+    commands
+        .spawn_bundle(menu_root())
+        .insert(MenuScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(menu_text(
+                asset_server,
+                "Choose a profile".to_string(),
+                60.0,
+            ));
+            for slot in 0..SAVE_SLOTS {
+                let save = read_save(slot);
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            align_items: AlignItems::Center,
+                            ..Default::default()
+                        },
+                        color: UiColor::from(Color::NONE),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn_bundle(menu_text(
+                            asset_server,
+                            slot_description(slot, &save),
+                            30.0,
+                        ));
+                        if save.is_some() {
+                            spawn_button(parent, asset_server, "Continue", SlotAction::Play(slot));
+                            let copy = SlotAction::Copy(slot);
+                            let delete = SlotAction::Delete(slot);
+                            spawn_button(
+                                parent,
+                                asset_server,
+                                if confirming == Some(copy) {
+                                    "Really copy?"
+                                } else {
+                                    "Copy"
+                                },
+                                copy,
+                            );
+                            spawn_button(
+                                parent,
+                                asset_server,
+                                if confirming == Some(delete) {
+                                    "Really delete?"
+                                } else {
+                                    "Delete"
+                                },
+                                delete,
+                            );
+                        } else {
+                            spawn_button(
+                                parent,
+                                asset_server,
+                                "New game",
+                                SlotAction::NewGame(slot),
+                            );
+                        }
+                    });
+            }
+            spawn_button(parent, asset_server, "Back", SlotAction::Back);
+        });
+}
+
+fn name_line(name: &str) -> String {
+    format!("{}_", name)
+}
+
+fn spawn_naming_menu(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    bindings: &InputBindings,
+    name: &str,
+) {
+    commands
+        .spawn_bundle(menu_root())
+        .insert(MenuScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(menu_text(
+                asset_server,
+                "Name your profile".to_string(),
+                60.0,
+            ));
+            parent
+                .spawn_bundle(menu_text(asset_server, name_line(name), 40.0))
+                .insert(ProfileNameText);
+            parent.spawn_bundle(menu_text(
+                asset_server,
+                format!(
+                    "Start with {} - Erase with {} - Go back with {}",
+                    bindings.description(Action::Confirm),
+                    bindings.description(Action::DeleteDigit),
+                    bindings.description(Action::Cancel)
+                ),
+                24.0,
+            ));
+            spawn_button(parent, asset_server, "Start", SlotAction::Start);
+            spawn_button(parent, asset_server, "Back", SlotAction::Back);
+        });
+}
+
+fn spawn_profile_menu_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut new_profile: ResMut<NewProfile>,
+    mut confirming: ResMut<ConfirmingAction>,
+) {
+    *new_profile = NewProfile::default();
+    **confirming = None;
+    spawn_profile_menu(&mut commands, &asset_server, None);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn profile_button_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    bindings: Res<InputBindings>,
+    actions: Res<Actions>,
+    mut state: ResMut<State<GameState>>,
+    mut new_profile: ResMut<NewProfile>,
+    mut confirming: ResMut<ConfirmingAction>,
+    mut characters: ResMut<Events<ReceivedCharacter>>,
+    mut load_slots: EventWriter<LoadSlot>,
+    buttons: Query<(&Interaction, &SlotAction), Changed<Interaction>>,
+    menus: Query<Entity, With<MenuScreen>>,
+) {
+    let clicked = buttons
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Clicked)
+        .map(|(_, action)| *action);
+    let pressed = if new_profile.slot.is_none() {
+        None
+    } else if actions.just_pressed(Action::Confirm) {
+        Some(SlotAction::Start)
+    } else if actions.just_pressed(Action::Cancel) {
+        Some(SlotAction::Back)
+    } else {
+        None
+    };
+    let action = match clicked.or(pressed) {
+        Some(action) => action,
+        None => return,
+    };
+    // Copying and deleting only run on a second click; any other button cancels them.
+    let confirmed = confirming.take() == Some(action);
+    match action {
+        SlotAction::Copy(_) | SlotAction::Delete(_) if !confirmed => **confirming = Some(action),
+        SlotAction::Play(slot) => {
+            load_slots.send(LoadSlot(slot));
+            return;
+        }
+        SlotAction::NewGame(slot) => {
+            new_profile.slot = Some(slot);
+            new_profile.name.clear();
+            // Characters typed before the screen opened don't belong to the name.
+            characters.clear();
+        }
+        SlotAction::Start => {
+            if let Some(slot) = new_profile.slot.take() {
+                write_save(
+                    slot,
+                    &SaveData {
+                        name: new_profile.name.trim().to_string(),
+                        ..Default::default()
+                    },
+                );
+                load_slots.send(LoadSlot(slot));
+            }
+            return;
+        }
+        SlotAction::Back => {
+            if new_profile.slot.take().is_none() {
+                state.set(GameState::MainMenu).unwrap();
+                return;
+            }
+        }
+        SlotAction::Copy(slot) => {
+            let free_slot = (0..SAVE_SLOTS).find(|slot| read_save(*slot).is_none());
+            match (free_slot, read_save(slot)) {
+                (Some(free_slot), Some(save)) => write_save(free_slot, &save),
+                _ => warn!("There is no free slot to copy slot {} into!", slot + 1),
+            }
+        }
+        SlotAction::Delete(slot) => delete_save(slot),
+    }
+    for menu in menus.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+    if new_profile.slot.is_some() {
+        spawn_naming_menu(&mut commands, &asset_server, &bindings, &new_profile.name);
+    } else {
+        spawn_profile_menu(&mut commands, &asset_server, **confirming);
+    }
+}
+
+fn type_profile_name_system(
+    mut characters: EventReader<ReceivedCharacter>,
+    actions: Res<Actions>,
+    mut new_profile: ResMut<NewProfile>,
+    mut name_text: Query<&mut Text, With<ProfileNameText>>,
+) {
+    if new_profile.slot.is_none() {
+        return;
+    }
+    for character in characters.iter() {
+        if !character.char.is_control() && new_profile.name.chars().count() < MAX_NAME_LENGTH {
+            new_profile.name.push(character.char);
+        }
+    }
+    if actions.just_pressed(Action::DeleteDigit) {
+        new_profile.name.pop();
+    }
+    if !new_profile.is_changed() {
+        return;
+    }
+    for mut text in name_text.iter_mut() {
+        text.sections[0].value = name_line(&new_profile.name);
+    }
+}
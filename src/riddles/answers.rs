@@ -0,0 +1,144 @@
+const WHEEL_PUNCTUATION: &str = "-'.,!?";
+
+#[derive(Default, Clone, Copy, Eq, PartialEq)]
+pub enum AnswerKind {
+    #[default]
+    Digits,
+    Letters,
+    Alphanumeric,
+    FreeText,
+}
+
+impl TryFrom<&str> for AnswerKind {
+    type Error = String;
+
+    fn try_from(kind: &str) -> Result<Self, Self::Error> {
+        match kind.to_lowercase().as_ref() {
+            "" | "digits" => Ok(Self::Digits),
+            "letters" => Ok(Self::Letters),
+            "alphanumeric" => Ok(Self::Alphanumeric),
+            "free_text" | "freetext" => Ok(Self::FreeText),
+            _ => Err(format!("unknown answer kind {}", kind)),
+        }
+    }
+}
+
+impl AnswerKind {
+    pub fn accepts(&self, character: char) -> bool {
+        match self {
+            Self::Digits => character.is_ascii_digit(),
+            Self::Letters => character.is_alphabetic(),
+            Self::Alphanumeric => character.is_alphanumeric(),
+            Self::FreeText => !character.is_control() && !character.is_whitespace(),
+        }
+    }
+
+    /// The characters offered by the on-screen wheel for answers of this kind.
+    pub fn wheel(&self) -> Vec<char> {
+        let digits = '0'..='9';
+        let letters = 'A'..='Z';
+        match self {
+            Self::Digits => digits.collect(),
+            Self::Letters => letters.collect(),
+            Self::Alphanumeric => digits.chain(letters).collect(),
+            Self::FreeText => digits
+                .chain(letters)
+                .chain(WHEEL_PUNCTUATION.chars())
+                .collect(),
+        }
+    }
+
+    /// Whether every character of the answer can be entered with this kind's wheel.
+    pub fn can_enter(&self, answer: &str) -> bool {
+        let wheel = self.wheel();
+        normalize_answer(answer)
+            .chars()
+            .flat_map(char::to_uppercase)
+            .all(|character| wheel.contains(&character))
+    }
+}
+
+pub fn normalize_answer(answer: &str) -> String {
+    answer
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_accept_only_digits() {
+        assert!(AnswerKind::Digits.accepts('7'));
+        assert!(!AnswerKind::Digits.accepts('a'));
+        assert!(!AnswerKind::Digits.accepts(' '));
+    }
+
+    #[test]
+    fn letters_accept_letters_of_any_alphabet() {
+        assert!(AnswerKind::Letters.accepts('a'));
+        assert!(AnswerKind::Letters.accepts('Ä'));
+        assert!(!AnswerKind::Letters.accepts('1'));
+        assert!(!AnswerKind::Letters.accepts('-'));
+    }
+
+    #[test]
+    fn alphanumeric_accepts_letters_and_digits() {
+        assert!(AnswerKind::Alphanumeric.accepts('a'));
+        assert!(AnswerKind::Alphanumeric.accepts('1'));
+        assert!(!AnswerKind::Alphanumeric.accepts('?'));
+    }
+
+    #[test]
+    fn free_text_accepts_punctuation_but_no_whitespace() {
+        assert!(AnswerKind::FreeText.accepts('?'));
+        assert!(AnswerKind::FreeText.accepts('\''));
+        assert!(!AnswerKind::FreeText.accepts(' '));
+        assert!(!AnswerKind::FreeText.accepts('\n'));
+        assert!(!AnswerKind::FreeText.accepts('\u{8}'));
+    }
+
+    #[test]
+    fn every_kind_accepts_its_wheel() {
+        for kind in [
+            AnswerKind::Digits,
+            AnswerKind::Letters,
+            AnswerKind::Alphanumeric,
+            AnswerKind::FreeText,
+        ] {
+            assert!(kind
+                .wheel()
+                .into_iter()
+                .all(|character| kind.accepts(character)));
+        }
+    }
+
+    #[test]
+    fn answers_must_fit_the_wheel() {
+        assert!(!AnswerKind::Digits.can_enter("cloud"));
+        assert!(AnswerKind::Digits.can_enter("4 2"));
+        assert!(AnswerKind::Letters.can_enter("New York"));
+        assert!(!AnswerKind::Letters.can_enter("it's"));
+        assert!(AnswerKind::FreeText.can_enter("Don't stop!"));
+        assert!(!AnswerKind::FreeText.can_enter("a+b"));
+    }
+
+    #[test]
+    fn normalizing_ignores_case() {
+        assert_eq!(normalize_answer("HeLLo"), "hello");
+    }
+
+    #[test]
+    fn normalizing_drops_whitespace() {
+        assert_eq!(normalize_answer(" New\tYork \n"), "newyork");
+        assert_eq!(normalize_answer("   "), "");
+    }
+
+    #[test]
+    fn normalizing_keeps_digits_and_punctuation() {
+        assert_eq!(normalize_answer("Don't 42!"), "don't42!");
+    }
+}
@@ -0,0 +1,168 @@
+use super::RiddleId;
+use crate::{
+    animation::{
+        AnimationEvent, AnimationGraph, Clip, Condition, PlayMode, SpriteAnimator, Transition,
+    },
+    player::Player,
+    GameState,
+};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_rapier2d::prelude::*;
+
+pub struct DoorsPlugin;
+
+impl Plugin for DoorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DoorOpened>()
+            .insert_resource(UnlockTimer(Timer::from_seconds(OPEN_DOOR_HOLD, false)))
+            .add_system_set(
+                SystemSet::on_enter(GameState::DoorOpening).with_system(start_unlock_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::DoorOpening)
+                    .with_system(open_door_system)
+                    .with_system(finish_unlock_system),
+            );
+    }
+}
+
+const OPEN_DOOR_FRAME: usize = 75;
+const CLOSED_DOOR_FRAME: usize = 76;
+const OPENING_FRAME_DURATION: f32 = 0.15;
+/// How long the camera keeps showing a door once it has opened, before player input unlocks.
+const OPEN_DOOR_HOLD: f32 = 0.6;
+/// The animation event sent when the opening clip shows the open door.
+const OPENED_EVENT: &str = "opened";
+
+/// Counts down the input lock once the door has opened.
+#[derive(Deref, DerefMut)]
+struct UnlockTimer(Timer);
+
+/// Sent when a riddle is solved and its door starts opening.
+pub struct DoorOpened {
+    pub door: Entity,
+    pub riddle: RiddleId,
+}
+
+#[derive(Default, Clone, Copy, Eq, PartialEq)]
+pub enum DoorColor {
+    #[default]
+    Blue,
+    Yellow,
+    Green,
+    Red,
+}
+
+impl TryFrom<&str> for DoorColor {
+    type Error = String;
+
+    fn try_from(color: &str) -> Result<Self, Self::Error> {
+        match color {
+            "BLUE" => Ok(Self::Blue),
+            "YELLOW" => Ok(Self::Yellow),
+            "GREEN" => Ok(Self::Green),
+            "RED" => Ok(Self::Red),
+            _ => Err(format!("unknown door color {}", color)),
+        }
+    }
+}
+
+impl DoorColor {
+    /// The column of the door in the door sprite sheet, next to the open door.
+    fn offset(&self) -> usize {
+        match self {
+            Self::Blue => 0,
+            Self::Yellow => 1,
+            Self::Green => 2,
+            Self::Red => 3,
+        }
+    }
+
+    /// The background of the riddle UI, a muted shade of the door's paint.
+    pub fn background(&self) -> Color {
+        match self {
+            Self::Blue => Color::rgb(0.5, 0.5, 0.85),
+            Self::Yellow => Color::rgb(0.8, 0.7, 0.35),
+            Self::Green => Color::rgb(0.35, 0.7, 0.5),
+            Self::Red => Color::rgb(0.8, 0.45, 0.35),
+        }
+    }
+
+    pub fn closed_frame(&self) -> usize {
+        CLOSED_DOOR_FRAME + self.offset()
+    }
+}
+
+fn still_clip(frame: usize) -> Clip {
+    Clip {
+        frames: vec![frame],
+        frame_duration: OPENING_FRAME_DURATION,
+        mode: PlayMode::Loop,
+        events: Vec::new(),
+    }
+}
+
+/// Builds the animator of a door that shows its color until it is opened.
+pub fn door_animator(color: DoorColor, open: bool) -> SpriteAnimator {
+    let closed = color.closed_frame();
+    SpriteAnimator::new(AnimationGraph {
+        initial: if open { "open" } else { "closed" }.to_string(),
+        clips: HashMap::from_iter([
+            ("closed".to_string(), still_clip(closed)),
+            (
+                "opening".to_string(),
+                Clip {
+                    // Doors only show the top half of their art, so the clip blinks between the
+                    // closed and open tops of the same row before settling open.
+                    frames: vec![closed, OPEN_DOOR_FRAME, closed, OPEN_DOOR_FRAME],
+                    frame_duration: OPENING_FRAME_DURATION,
+                    mode: PlayMode::Once,
+                    events: vec![(3, OPENED_EVENT.to_string())],
+                },
+            ),
+            ("open".to_string(), still_clip(OPEN_DOOR_FRAME)),
+        ]),
+        transitions: vec![Transition {
+            from: Some("opening".to_string()),
+            to: "open".to_string(),
+            when: vec![Condition::Finished],
+        }],
+    })
+}
+
+fn start_unlock_system(
+    mut unlock_timer: ResMut<UnlockTimer>,
+    mut player_info: Query<&mut Velocity, With<Player>>,
+) {
+    // The timer only starts once the door's animation reports it open.
+    unlock_timer.reset();
+    unlock_timer.pause();
+    for mut velocity in player_info.iter_mut() {
+        velocity.linvel.x = 0.0;
+    }
+}
+
+fn open_door_system(mut events: EventReader<DoorOpened>, mut doors: Query<&mut SpriteAnimator>) {
+    for event in events.iter() {
+        if let Ok(mut animator) = doors.get_mut(event.door) {
+            animator.play("opening");
+        }
+    }
+}
+
+fn finish_unlock_system(
+    time: Res<Time>,
+    mut animation_events: EventReader<AnimationEvent>,
+    mut unlock_timer: ResMut<UnlockTimer>,
+    mut state: ResMut<State<GameState>>,
+) {
+    if animation_events
+        .iter()
+        .any(|event| event.name == OPENED_EVENT)
+    {
+        unlock_timer.unpause();
+    }
+    if unlock_timer.tick(time.delta()).just_finished() {
+        state.set(GameState::MapExploring).unwrap();
+    }
+}
@@ -1,43 +1,133 @@
-use crate::{map::CurrentLevel, player::Player, GameState};
+use crate::{
+    actions::{Action, Actions, InputBindings},
+    map::{from_entity_or_default, CurrentLevel, EntityError, FieldProblem, Fields},
+    player::Player,
+    progress::PlayTime,
+    GameState,
+};
+use answers::{normalize_answer, AnswerKind};
 use bevy::{
     prelude::*,
     utils::{HashMap, HashSet},
 };
 use bevy_ecs_ldtk::prelude::*;
 use bevy_rapier2d::prelude::*;
+use doors::{door_animator, DoorsPlugin};
+pub use doors::{DoorColor, DoorOpened};
+use serde::{Deserialize, Serialize};
+use wheel::{CharacterWheel, WheelPlugin};
 
+mod answers;
+mod doors;
 mod nodes;
+mod wheel;
 
 pub struct RiddlesPlugin;
 
 impl Plugin for RiddlesPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(AnsweredRiddles::new())
+        app.add_plugin(WheelPlugin)
+            .add_plugin(DoorsPlugin)
+            .init_resource::<AnsweredRiddles>()
+            .init_resource::<LegacyAnsweredRiddles>()
+            .init_resource::<HintsUsed>()
+            .init_resource::<RiddleAttempts>()
+            .init_resource::<RiddleLockouts>()
+            .init_resource::<RiddleJournal>()
+            .add_event::<WrongAnswer>()
+            .init_resource::<AnswerPalette>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::LevelLoading).with_system(despawn_riddles_system),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::MainMenu).with_system(despawn_riddles_system),
+            )
             .add_system_set(
                 SystemSet::on_exit(GameState::LevelLoading).with_system(init_riddles_system),
             )
             .add_system_set(
-                SystemSet::on_update(GameState::MapExploring).with_system(touch_door_system),
+                SystemSet::on_enter(GameState::RiddleSolving)
+                    .with_system(drop_typed_characters_system),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::MapExploring)
+                    .with_system(touch_door_system.label(TouchDoor))
+                    .with_system(door_lockout_system)
+                    .with_system(lockout_notice_system),
             )
             .add_system_set(
                 SystemSet::on_update(GameState::RiddleSolving)
                     .with_system(answering_riddle_system)
                     .with_system(delete_digit_system)
+                    .with_system(reveal_hint_system)
                     .with_system(correct_answer_system)
-                    .with_system(close_riddle_system),
+                    .with_system(wrong_answer_system.after(correct_answer_system))
+                    .with_system(wrong_answer_feedback_system)
+                    .with_system(close_riddle_system.after(wrong_answer_system)),
             );
     }
 }
 
-type AnsweredRiddles = HashSet<String>;
+const MAX_ANSWER_LENGTH: usize = 12;
+const DEFAULT_ANSWER_PALETTE: [Color; 4] = [Color::RED, Color::BLUE, Color::YELLOW, Color::GREEN];
+const FEEDBACK_DURATION: f32 = 0.4;
+const SHAKE_DISTANCE: f32 = 12.0;
+const SHAKE_FREQUENCY: f32 = 40.0;
+const FLASH_COLOR: Color = Color::rgba(0.9, 0.1, 0.1, 0.6);
+const LOCKOUT_NOTICE_DURATION: f32 = 2.0;
+/// How far above the center of a locked door its notice is shown.
+const LOCKOUT_NOTICE_OFFSET: f32 = 48.0;
+
+#[derive(Default, Deref, DerefMut)]
+pub struct AnsweredRiddles(HashSet<RiddleId>);
+
+/// Questions of riddles solved by saves made before riddles were identified by their door.
+#[derive(Default, Deref, DerefMut)]
+pub struct LegacyAnsweredRiddles(HashSet<String>);
+
+/// How many hints of each riddle the player has revealed.
+#[derive(Default, Deref, DerefMut)]
+pub struct HintsUsed(HashMap<RiddleId, usize>);
+
+/// How many wrong answers the player has given to each riddle.
+#[derive(Default, Deref, DerefMut)]
+pub struct RiddleAttempts(HashMap<RiddleId, usize>);
+
+/// The play time, in seconds, at which locked out riddles can be answered again.
+#[derive(Default, Deref, DerefMut)]
+pub struct RiddleLockouts(HashMap<RiddleId, f32>);
+
+/// The riddles the player has opened, listed in the journal.
+#[derive(Default, Deref, DerefMut)]
+pub struct RiddleJournal(HashMap<RiddleId, JournalEntry>);
+
+/// The colors the answer slots of doors without their own `answer_colors` cycle through.
+/// Insert it before adding the plugin to replace the default palette; an empty palette falls
+/// back to the default one.
+#[derive(Deref, DerefMut)]
+pub struct AnswerPalette(pub Vec<Color>);
+
+impl Default for AnswerPalette {
+    fn default() -> Self {
+        Self(Vec::from(DEFAULT_ANSWER_PALETTE))
+    }
+}
+
+/// Labels the system that opens doors, so other uses of the interact key can yield to it.
+#[derive(SystemLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TouchDoor;
 
 #[derive(Component)]
 struct RiddleNode;
 
+#[derive(Component)]
+struct HintText;
+
 #[derive(Component)]
 struct AnswerContainer {
     index: usize,
     answer_length: usize,
+    answer_kind: AnswerKind,
 }
 
 #[derive(Component)]
@@ -45,104 +135,278 @@ struct Answer {
     position: usize,
 }
 
+/// Tells the player how long a door they tried to open stays locked.
+#[derive(Component)]
+struct LockoutNotice {
+    timer: Timer,
+}
+
+#[derive(Component)]
+struct WrongAnswerFeedback {
+    timer: Timer,
+}
+
+struct WrongAnswer;
+
+#[derive(Default, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+pub struct RiddleId {
+    pub level: String,
+    pub entity: String,
+}
+
+/// What the journal remembers of a riddle; the answer is only shown once it is solved.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub question: String,
+    pub answer: String,
+}
+
 #[derive(Default, Component)]
 pub struct RiddleInfo {
+    id: RiddleId,
     question: String,
     answer: String,
+    answer_kind: AnswerKind,
+    answer_colors: Vec<Color>,
+    color: DoorColor,
+    hints: Vec<String>,
+    max_attempts: Option<usize>,
+    lockout_seconds: f32,
     riddle: Option<Entity>,
     active: bool,
     next_level: String,
 }
 
+impl TryFrom<&EntityInstance> for RiddleInfo {
+    type Error = EntityError;
+
+    fn try_from(entity_instance: &EntityInstance) -> Result<Self, Self::Error> {
+        let fields = Fields::new(entity_instance);
+        let question = fields.string("question")?;
+        if question.trim().is_empty() {
+            return Err(fields.error(
+                "question",
+                FieldProblem::Invalid("must not be empty".to_string()),
+            ));
+        }
+        let answer_kind = AnswerKind::try_from(
+            fields
+                .optional_string("answer_kind")?
+                .unwrap_or_default()
+                .as_str(),
+        )
+        .map_err(|reason| fields.error("answer_kind", FieldProblem::Invalid(reason)))?;
+        let answer = fields.string("answer")?;
+        if !(1..=MAX_ANSWER_LENGTH).contains(&normalize_answer(&answer).chars().count()) {
+            return Err(fields.error(
+                "answer",
+                FieldProblem::Invalid(format!(
+                    "expected between 1 and {} characters besides whitespace",
+                    MAX_ANSWER_LENGTH
+                )),
+            ));
+        }
+        if !answer_kind.can_enter(&answer) {
+            return Err(fields.error(
+                "answer",
+                FieldProblem::Invalid(
+                    "contains characters the answer_kind's wheel can't enter".to_string(),
+                ),
+            ));
+        }
+        let color = fields
+            .optional_string("color")?
+            .map(|color| DoorColor::try_from(color.as_str()))
+            .transpose()
+            .map_err(|reason| fields.error("color", FieldProblem::Invalid(reason)))?
+            .unwrap_or_default();
+        let max_attempts = fields
+            .optional_int("max_attempts")?
+            .filter(|max_attempts| *max_attempts > 0)
+            .map(|max_attempts| max_attempts as usize);
+        Ok(Self {
+            id: RiddleId {
+                entity: entity_instance.iid.clone(),
+                ..Default::default()
+            },
+            question,
+            answer,
+            answer_kind,
+            answer_colors: fields.colors("answer_colors")?,
+            color,
+            hints: fields.strings("hints")?,
+            max_attempts,
+            lockout_seconds: fields
+                .optional_float("lockout_seconds")?
+                .unwrap_or_default(),
+            next_level: fields.string("next_level")?,
+            ..Default::default()
+        })
+    }
+}
+
 impl From<EntityInstance> for RiddleInfo {
     fn from(entity_instance: EntityInstance) -> Self {
-        let fields = HashMap::from_iter(entity_instance.field_instances.iter().map(|field| {
-            (
-                field.identifier.clone(),
-                match field.value.clone() {
-                    FieldValue::String(Some(value)) => value,
-                    _ => "".to_string(),
-                },
-            )
-        }));
-        Self {
-            question: fields
-                .get("question")
-                .expect("A question is required for a riddle!")
-                .clone(),
-            answer: fields
-                .get("answer")
-                .expect("An answer is required for a riddle!")
-                .clone(),
-            next_level: fields
-                .get("next_level")
-                .expect("A next level is required for a riddle!")
-                .clone(),
-            ..Default::default()
-        }
+        from_entity_or_default(&entity_instance)
     }
 }
 
+#[allow(clippy::type_complexity)]
+fn despawn_riddles_system(
+    mut commands: Commands,
+    riddle_nodes: Query<Entity, Or<(With<RiddleNode>, With<LockoutNotice>)>>,
+) {
+    for riddle_node in riddle_nodes.iter() {
+        commands.entity(riddle_node).despawn_recursive();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn init_riddles_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    answered_riddles: Res<AnsweredRiddles>,
-    mut doors: Query<(&mut RiddleInfo, &mut TextureAtlasSprite)>,
+    current_level: Res<CurrentLevel>,
+    answer_palette: Res<AnswerPalette>,
+    hints_used: Res<HintsUsed>,
+    bindings: Res<InputBindings>,
+    mut answered_riddles: ResMut<AnsweredRiddles>,
+    legacy_answered_riddles: Res<LegacyAnsweredRiddles>,
+    mut journal: ResMut<RiddleJournal>,
+    mut doors: Query<(Entity, &mut RiddleInfo)>,
 ) {
     use nodes::*;
 
-    for (mut door, mut sprite) in doors.iter_mut() {
-        if answered_riddles.contains(&door.question) {
-            sprite.index = 75;
+    let hint_inputs = bindings.description(Action::RevealHint);
+    for (entity, mut door) in doors.iter_mut() {
+        door.id.level.clone_from(&current_level);
+        // Legacy questions are kept, since doors in other levels may ask the same question.
+        if legacy_answered_riddles.contains(&door.question)
+            && answered_riddles.insert(door.id.clone())
+        {
+            journal
+                .entry(door.id.clone())
+                .or_insert_with(|| JournalEntry {
+                    question: door.question.clone(),
+                    answer: door.answer.clone(),
+                });
+        }
+        let answered = answered_riddles.contains(&door.id);
+        commands
+            .entity(entity)
+            .insert(door_animator(door.color, answered));
+        if answered {
             continue;
         }
+        // Answers are compared without whitespace, so multi-word answers get no slots for spaces.
+        let answer_length = normalize_answer(&door.answer).chars().count();
+        let colors = if !door.answer_colors.is_empty() {
+            door.answer_colors.clone()
+        } else if !answer_palette.is_empty() {
+            answer_palette.to_vec()
+        } else {
+            // An empty palette would leave the riddle without answer slots.
+            Vec::from(DEFAULT_ANSWER_PALETTE)
+        };
         door.riddle = Some(
             commands
-                .spawn_bundle(root_node())
+                .spawn_bundle(root_node(door.color.background()))
                 .insert(RiddleNode)
                 .with_children(|parent| {
                     parent.spawn_bundle(question_text(&asset_server, &door.question));
+                    parent
+                        .spawn_bundle(hint_text(
+                            &asset_server,
+                            revealed_hints(
+                                &door.hints,
+                                hints_used.get(&door.id).copied().unwrap_or_default(),
+                                &hint_inputs,
+                            ),
+                        ))
+                        .insert(HintText);
                     parent
                         .spawn_bundle(answer_container())
                         .insert(AnswerContainer {
                             index: 0,
-                            answer_length: door.answer.len(),
+                            answer_length,
+                            answer_kind: door.answer_kind,
                         })
                         .with_children(|parent| {
-                            parent
-                                .spawn_bundle(answer_position(&asset_server, Color::RED))
-                                .insert(Answer { position: 0 });
-                            parent
-                                .spawn_bundle(answer_position(&asset_server, Color::BLUE))
-                                .insert(Answer { position: 1 });
-                            parent
-                                .spawn_bundle(answer_position(&asset_server, Color::YELLOW))
-                                .insert(Answer { position: 2 });
+                            for (position, color) in
+                                colors.iter().cycle().take(answer_length).enumerate()
+                            {
+                                parent
+                                    .spawn_bundle(answer_position(&asset_server, *color))
+                                    .insert(Answer { position });
+                            }
                         });
+                    parent
+                        .spawn_bundle(character_wheel(&asset_server))
+                        .insert(CharacterWheel);
                 })
                 .id(),
         );
     }
 }
 
+fn revealed_hints(hints: &[String], revealed: usize, hint_inputs: &str) -> String {
+    if hints.is_empty() {
+        String::new()
+    } else if revealed == 0 {
+        format!(
+            "Press {} for a hint ({} available)",
+            hint_inputs,
+            hints.len()
+        )
+    } else {
+        hints[..revealed.min(hints.len())].join("\n")
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn touch_door_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
     rapier_context: Res<RapierContext>,
+    play_time: Res<PlayTime>,
     answered_riddles: Res<AnsweredRiddles>,
-    mut keyboard_input: ResMut<Input<KeyCode>>,
+    lockouts: Res<RiddleLockouts>,
+    mut journal: ResMut<RiddleJournal>,
+    mut actions: ResMut<Actions>,
     mut state: ResMut<State<GameState>>,
     mut current_level: ResMut<CurrentLevel>,
     player_info: Query<Entity, With<Player>>,
-    mut doors: Query<(Entity, &mut RiddleInfo)>,
+    mut doors: Query<(Entity, &GlobalTransform, &mut RiddleInfo)>,
     mut riddle_nodes: Query<(&mut Style, &mut Visibility), With<RiddleNode>>,
+    notices: Query<Entity, With<LockoutNotice>>,
 ) {
     let player = player_info.single();
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        for (door, mut riddle_info) in doors.iter_mut() {
+    if actions.just_pressed(Action::Interact) {
+        for (door, door_transform, mut riddle_info) in doors.iter_mut() {
             if let Some(contact_pair) = rapier_context.intersection_pair(player, door) {
                 if !contact_pair {
                     continue;
                 }
-                if !answered_riddles.contains(&riddle_info.question) {
+                if !answered_riddles.contains(&riddle_info.id) {
+                    let remaining = lockouts
+                        .get(&riddle_info.id)
+                        .map(|expiry| expiry - play_time.elapsed_secs())
+                        .unwrap_or_default();
+                    if remaining > 0.0 {
+                        for notice in notices.iter() {
+                            commands.entity(notice).despawn();
+                        }
+                        commands
+                            .spawn_bundle(nodes::lockout_notice(
+                                &asset_server,
+                                format!("Locked, try again in {:.0}s", remaining.ceil()),
+                                door_transform.translation() + Vec3::Y * LOCKOUT_NOTICE_OFFSET,
+                            ))
+                            .insert(LockoutNotice {
+                                timer: Timer::from_seconds(LOCKOUT_NOTICE_DURATION, false),
+                            });
+                        actions.consume(Action::Interact);
+                        return;
+                    }
                     let (mut node_style, mut node_visibility) = riddle_nodes
                         .get_mut(riddle_info.riddle.expect(
                             "The riddle entity is supposed to be set by the init_riddles_system!",
@@ -151,10 +415,20 @@ fn touch_door_system(
                     node_style.display = Display::Flex;
                     node_visibility.is_visible = true;
                     riddle_info.active = true;
+                    if !journal.contains_key(&riddle_info.id) {
+                        journal.insert(
+                            riddle_info.id.clone(),
+                            JournalEntry {
+                                question: riddle_info.question.clone(),
+                                answer: riddle_info.answer.clone(),
+                            },
+                        );
+                    }
+                    actions.consume(Action::Interact);
                     state.set(GameState::RiddleSolving).unwrap();
                     return;
                 }
-                keyboard_input.reset(KeyCode::Space);
+                actions.consume(Action::Interact);
                 current_level.clone_from(&riddle_info.next_level);
                 state.set(GameState::LevelLoading).unwrap();
                 return;
@@ -163,19 +437,25 @@ fn touch_door_system(
     }
 }
 
+/// Drops characters typed while exploring, such as the key that opened the riddle, so they
+/// don't end up in the answer.
+fn drop_typed_characters_system(mut characters: ResMut<Events<ReceivedCharacter>>) {
+    characters.clear();
+}
+
 fn answering_riddle_system(
     mut input: EventReader<ReceivedCharacter>,
     mut container_info: Query<(&mut AnswerContainer, &ComputedVisibility)>,
     mut answer_nodes: Query<(&mut Text, &ComputedVisibility, &Answer)>,
 ) {
     for character in input.iter() {
-        if !('0'..='9').contains(&character.char) {
-            continue;
-        }
         let (mut container, _) = container_info
             .iter_mut()
             .find(|(_, visibility)| visibility.is_visible())
             .expect("A visible container is expected while this system is running!");
+        if !container.answer_kind.accepts(character.char) {
+            continue;
+        }
         let (mut answer, _, _) = answer_nodes
             .iter_mut()
             .find(|(_, visibility, answer)| visibility.is_visible() && answer.position == container.index)
@@ -186,11 +466,11 @@ fn answering_riddle_system(
 }
 
 fn delete_digit_system(
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Actions>,
     mut container_info: Query<(&mut AnswerContainer, &ComputedVisibility)>,
     mut answer_nodes: Query<(&mut Text, &ComputedVisibility, &Answer)>,
 ) {
-    if !keyboard_input.just_pressed(KeyCode::Back) {
+    if !actions.just_pressed(Action::DeleteDigit) {
         return;
     }
     let (mut container, _) = container_info
@@ -208,15 +488,43 @@ fn delete_digit_system(
     answer.sections[0].value = "_".to_string();
 }
 
+fn reveal_hint_system(
+    actions: Res<Actions>,
+    mut hints_used: ResMut<HintsUsed>,
+    doors: Query<&RiddleInfo>,
+    mut hint_nodes: Query<(&mut Text, &ComputedVisibility), With<HintText>>,
+) {
+    if !actions.just_pressed(Action::RevealHint) {
+        return;
+    }
+    let door = doors
+        .iter()
+        .find(|door| door.active)
+        .expect("Exactly one active door is expected while this system is running!");
+    let revealed = hints_used.get(&door.id).copied().unwrap_or_default();
+    if revealed >= door.hints.len() {
+        return;
+    }
+    hints_used.insert(door.id.clone(), revealed + 1);
+    let (mut hint, _) = hint_nodes
+        .iter_mut()
+        .find(|(_, visibility)| visibility.is_visible())
+        .expect("The visible riddle is expected to have a hint node!");
+    hint.sections[0].value = revealed_hints(&door.hints, revealed + 1, "");
+}
+
+#[allow(clippy::too_many_arguments)]
 fn correct_answer_system(
     mut commands: Commands,
-    keyboard_input: Res<Input<KeyCode>>,
+    actions: Res<Actions>,
     mut answered_riddles: ResMut<AnsweredRiddles>,
     mut state: ResMut<State<GameState>>,
-    mut doors: Query<(&mut RiddleInfo, &mut TextureAtlasSprite)>,
+    mut wrong_answers: EventWriter<WrongAnswer>,
+    mut opened_doors: EventWriter<DoorOpened>,
+    mut doors: Query<(Entity, &mut RiddleInfo)>,
     answer_nodes: Query<(&Text, &ComputedVisibility, &Answer)>,
 ) {
-    if keyboard_input.any_just_pressed([KeyCode::Return, KeyCode::NumpadEnter]) {
+    if actions.just_pressed(Action::Confirm) {
         let mut answer_nodes = Vec::from_iter(
             answer_nodes
                 .iter()
@@ -228,33 +536,149 @@ fn correct_answer_system(
             .into_iter()
             .map(|(_, value)| value)
             .collect::<String>();
-        let (mut door, mut sprite) = doors
+        let (entity, mut door) = doors
             .iter_mut()
-            .find(|(door, _)| door.active)
+            .find(|(_, door)| door.active)
             .expect("Only one door should be active while answering a riddle!");
-        if answer != door.answer {
+        if normalize_answer(&answer) != normalize_answer(&door.answer) {
+            wrong_answers.send(WrongAnswer);
             return;
         }
-        answered_riddles.insert(door.question.clone());
+        if state.set(GameState::DoorOpening).is_err() {
+            return;
+        }
+        answered_riddles.insert(door.id.clone());
         commands
             .entity(
                 door.riddle
                     .expect("The riddle entity is supposed to be set by the init_riddles_system!"),
             )
             .despawn_recursive();
-        sprite.index = 75;
         door.active = false;
-        state.set(GameState::MapExploring).unwrap();
+        opened_doors.send(DoorOpened {
+            door: entity,
+            riddle: door.id.clone(),
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn wrong_answer_system(
+    mut commands: Commands,
+    mut events: EventReader<WrongAnswer>,
+    play_time: Res<PlayTime>,
+    mut attempts: ResMut<RiddleAttempts>,
+    mut lockouts: ResMut<RiddleLockouts>,
+    mut state: ResMut<State<GameState>>,
+    mut doors: Query<&mut RiddleInfo>,
+    mut riddle_nodes: Query<(&mut Style, &mut Visibility), With<RiddleNode>>,
+    mut container_info: Query<(Entity, &mut AnswerContainer, &ComputedVisibility)>,
+    mut answer_nodes: Query<(&mut Text, &ComputedVisibility), With<Answer>>,
+) {
+    if events.iter().count() == 0 {
+        return;
+    }
+    let (container_entity, mut container, _) = container_info
+        .iter_mut()
+        .find(|(_, _, visibility)| visibility.is_visible())
+        .expect("A visible container is expected while this system is running!");
+    container.index = 0;
+    for (mut answer, _) in answer_nodes
+        .iter_mut()
+        .filter(|(_, visibility)| visibility.is_visible())
+    {
+        answer.sections[0].value = "_".to_string();
+    }
+    commands
+        .entity(container_entity)
+        .insert(WrongAnswerFeedback {
+            timer: Timer::from_seconds(FEEDBACK_DURATION, false),
+        });
+
+    let mut door = doors
+        .iter_mut()
+        .find(|door| door.active)
+        .expect("Exactly one active door is expected while this system is running!");
+    let door_attempts = attempts.entry(door.id.clone()).or_default();
+    *door_attempts += 1;
+    if let Some(max_attempts) = door.max_attempts {
+        if *door_attempts % max_attempts == 0
+            && door.lockout_seconds > 0.0
+            && state.set(GameState::MapExploring).is_ok()
+        {
+            lockouts.insert(
+                door.id.clone(),
+                play_time.elapsed_secs() + door.lockout_seconds,
+            );
+            door.active = false;
+            let (mut riddle_style, mut riddle_visibility) =
+                riddle_nodes
+                    .get_mut(door.riddle.expect(
+                        "The riddle entity is supposed to be set by the init_riddles_system!",
+                    ))
+                    .unwrap();
+            riddle_style.display = Display::None;
+            riddle_visibility.is_visible = false;
+        }
+    }
+}
+
+fn wrong_answer_feedback_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut containers: Query<(Entity, &mut Style, &mut UiColor, &mut WrongAnswerFeedback)>,
+) {
+    for (container, mut style, mut color, mut feedback) in containers.iter_mut() {
+        if feedback.timer.tick(time.delta()).finished() {
+            style.position.left = Val::Px(0.0);
+            *color = UiColor::from(Color::NONE);
+            commands.entity(container).remove::<WrongAnswerFeedback>();
+            continue;
+        }
+        let remaining = 1.0 - feedback.timer.percent();
+        style.position.left = Val::Px(
+            (feedback.timer.elapsed_secs() * SHAKE_FREQUENCY).sin() * SHAKE_DISTANCE * remaining,
+        );
+        let mut flash = FLASH_COLOR;
+        flash.set_a(FLASH_COLOR.a() * remaining);
+        *color = UiColor::from(flash);
+    }
+}
+
+/// Forgets lockouts that have run out, so they are no longer saved.
+fn door_lockout_system(play_time: Res<PlayTime>, mut lockouts: ResMut<RiddleLockouts>) {
+    let now = play_time.elapsed_secs();
+    // Only touch the lockouts when one expires, since changing them triggers an autosave.
+    if lockouts.values().any(|expiry| *expiry <= now) {
+        lockouts.retain(|_, expiry| *expiry > now);
+    }
+}
+
+fn lockout_notice_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut notices: Query<(Entity, &mut LockoutNotice)>,
+) {
+    for (entity, mut notice) in notices.iter_mut() {
+        if notice.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+        }
     }
 }
 
 fn close_riddle_system(
-    keyboard_input: Res<Input<KeyCode>>,
+    mut actions: ResMut<Actions>,
     mut state: ResMut<State<GameState>>,
     mut doors: Query<&mut RiddleInfo>,
     mut riddle_nodes: Query<(&mut Style, &mut Visibility), With<RiddleNode>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
+    if actions.just_pressed(Action::Cancel) {
+        // Otherwise the pause menu opens in the same frame, once the map is explored again.
+        actions.consume(Action::Cancel);
+        // A solved or locked out riddle is already closed and has queued its own transition.
+        if state.set(GameState::MapExploring).is_err() {
+            return;
+        }
         let mut door = doors
             .iter_mut()
             .find(|door| door.active)
@@ -266,6 +690,5 @@ fn close_riddle_system(
             .expect("Exactly one visible riddle node is expected while this system is running!");
         riddle_style.display = Display::None;
         riddle_visibility.is_visible = false;
-        state.set(GameState::MapExploring).unwrap();
     }
 }
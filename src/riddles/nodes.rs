@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-pub fn root_node() -> NodeBundle {
+pub fn root_node(background: Color) -> NodeBundle {
     NodeBundle {
         style: Style {
             display: Display::None,
@@ -10,7 +10,7 @@ pub fn root_node() -> NodeBundle {
             size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
             ..Default::default()
         },
-        color: UiColor::from(Color::rgb(0.5, 0.5, 0.85)),
+        color: UiColor::from(background),
         visibility: Visibility { is_visible: false },
         ..Default::default()
     }
@@ -56,3 +56,51 @@ pub fn answer_position(asset_server: &Res<AssetServer>, color: Color) -> TextBun
         ..Default::default()
     }
 }
+
+pub fn hint_text(asset_server: &Res<AssetServer>, hint: String) -> TextBundle {
+    TextBundle {
+        text: Text::from_section(
+            hint,
+            TextStyle {
+                font: asset_server.load("fonts/MontserratAlternates-MediumItalic.ttf"),
+                font_size: 30.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_alignment(TextAlignment::CENTER),
+        ..Default::default()
+    }
+}
+
+pub fn lockout_notice(
+    asset_server: &Res<AssetServer>,
+    text: String,
+    position: Vec3,
+) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(
+            text,
+            TextStyle {
+                font: asset_server.load("fonts/MontserratAlternates-MediumItalic.ttf"),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_alignment(TextAlignment::CENTER),
+        transform: Transform::from_translation(position),
+        ..Default::default()
+    }
+}
+
+pub fn character_wheel(asset_server: &Res<AssetServer>) -> TextBundle {
+    let style = |font_size| TextStyle {
+        font: asset_server.load("fonts/MontserratAlternates-MediumItalic.ttf"),
+        font_size,
+        color: Color::WHITE,
+    };
+    TextBundle::from_sections([
+        TextSection::new(String::new(), style(30.0)),
+        TextSection::new(String::new(), style(45.0)),
+        TextSection::new(String::new(), style(30.0)),
+    ])
+}
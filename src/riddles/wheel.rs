@@ -0,0 +1,139 @@
+use super::{Answer, AnswerContainer};
+use crate::{
+    actions::{Action, Actions},
+    GameState,
+};
+use bevy::prelude::*;
+
+pub struct WheelPlugin;
+
+impl Plugin for WheelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::RiddleSolving)
+                .with_system(select_slot_system)
+                .with_system(turn_wheel_system)
+                .with_system(show_wheel_system),
+        );
+    }
+}
+
+const EMPTY_SLOT: &str = "_";
+const SLOT_FONT_SIZE: f32 = 60.0;
+const SELECTED_SLOT_FONT_SIZE: f32 = 80.0;
+
+/// Shows the characters around the one in the selected answer slot.
+#[derive(Component)]
+pub struct CharacterWheel;
+
+fn step(length: usize, index: usize, forward: bool) -> usize {
+    if forward {
+        (index + 1) % length
+    } else {
+        (index + length - 1) % length
+    }
+}
+
+/// Finds the character of an answer slot on the wheel, or `None` for an empty slot.
+fn wheel_position(wheel: &[char], value: &str) -> Option<usize> {
+    let character = value.chars().next()?;
+    wheel
+        .iter()
+        .position(|wheel_character| wheel_character.eq_ignore_ascii_case(&character))
+}
+
+fn select_slot_system(
+    actions: Res<Actions>,
+    mut container_info: Query<(&mut AnswerContainer, &ComputedVisibility)>,
+) {
+    let forward = if actions.just_pressed(Action::NextSlot) {
+        true
+    } else if actions.just_pressed(Action::PreviousSlot) {
+        false
+    } else {
+        return;
+    };
+    let (mut container, _) = container_info
+        .iter_mut()
+        .find(|(_, visibility)| visibility.is_visible())
+        .expect("A visible container is expected while this system is running!");
+    container.index = step(container.answer_length, container.index, forward);
+}
+
+fn turn_wheel_system(
+    actions: Res<Actions>,
+    container_info: Query<(&AnswerContainer, &ComputedVisibility)>,
+    mut answer_nodes: Query<(&mut Text, &ComputedVisibility, &Answer)>,
+) {
+    let forward = if actions.just_pressed(Action::NextCharacter) {
+        true
+    } else if actions.just_pressed(Action::PreviousCharacter) {
+        false
+    } else {
+        return;
+    };
+    let (container, _) = container_info
+        .iter()
+        .find(|(_, visibility)| visibility.is_visible())
+        .expect("A visible container is expected while this system is running!");
+    let wheel = container.answer_kind.wheel();
+    let (mut answer, _, _) = answer_nodes
+            .iter_mut()
+            .find(|(_, visibility, answer)| visibility.is_visible() && answer.position == container.index)
+            .expect("The container is expected to have answer positions and the container's index is always valid!");
+    let next = match wheel_position(&wheel, &answer.sections[0].value) {
+        Some(index) => step(wheel.len(), index, forward),
+        None if forward => 0,
+        None => wheel.len() - 1,
+    };
+    answer.sections[0].value = wheel[next].to_string();
+}
+
+fn show_wheel_system(
+    container_info: Query<(&AnswerContainer, &ComputedVisibility)>,
+    mut answer_nodes: Query<(&mut Text, &ComputedVisibility, &Answer), Without<CharacterWheel>>,
+    mut wheel_nodes: Query<(&mut Text, &ComputedVisibility), With<CharacterWheel>>,
+) {
+    let (container, _) = match container_info
+        .iter()
+        .find(|(_, visibility)| visibility.is_visible())
+    {
+        Some(container) => container,
+        None => return,
+    };
+    let mut selected = EMPTY_SLOT.to_string();
+    for (mut answer, _, position) in answer_nodes
+        .iter_mut()
+        .filter(|(_, visibility, _)| visibility.is_visible())
+    {
+        let font_size = if position.position == container.index {
+            selected.clone_from(&answer.sections[0].value);
+            SELECTED_SLOT_FONT_SIZE
+        } else {
+            SLOT_FONT_SIZE
+        };
+        if answer.sections[0].style.font_size != font_size {
+            answer.sections[0].style.font_size = font_size;
+        }
+    }
+
+    let wheel = container.answer_kind.wheel();
+    let (previous, next) = match wheel_position(&wheel, &selected) {
+        Some(index) => (
+            wheel[step(wheel.len(), index, false)],
+            wheel[step(wheel.len(), index, true)],
+        ),
+        None => (wheel[wheel.len() - 1], wheel[0]),
+    };
+    let values = [format!("{}   ", previous), selected, format!("   {}", next)];
+    if let Some((mut wheel_text, _)) = wheel_nodes
+        .iter_mut()
+        .find(|(_, visibility)| visibility.is_visible())
+    {
+        for (section, value) in wheel_text.sections.iter_mut().zip(values) {
+            if section.value != value {
+                section.value = value;
+            }
+        }
+    }
+}
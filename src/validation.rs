@@ -0,0 +1,84 @@
+use crate::map::{box_image_tile, validate_entity, Fields, STARTING_LEVEL};
+use bevy::utils::HashSet;
+use bevy_ecs_ldtk::ldtk::{EntityInstance, LdtkJson, Level};
+use std::{fmt, fs};
+
+/// A problem found in the LDtk project, together with the level it was found in.
+pub struct Issue {
+    pub level: String,
+    pub message: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.level, self.message)
+    }
+}
+
+pub fn load_project(path: &str) -> Result<LdtkJson, String> {
+    let contents = fs::read_to_string(path).map_err(|error| error.to_string())?;
+    serde_json::from_str(&contents).map_err(|error| error.to_string())
+}
+
+pub fn level_entities(level: &Level) -> impl Iterator<Item = &EntityInstance> {
+    level
+        .layer_instances
+        .iter()
+        .flatten()
+        .flat_map(|layer| layer.entity_instances.iter())
+}
+
+pub fn validate_project(project: &LdtkJson) -> Vec<Issue> {
+    let level_iids = HashSet::from_iter(project.levels.iter().map(|level| level.iid.as_str()));
+    let mut issues = Vec::new();
+    if !level_iids.contains(STARTING_LEVEL) {
+        issues.push(Issue {
+            level: "project".to_string(),
+            message: format!("the starting level {} does not exist", STARTING_LEVEL),
+        });
+    }
+
+    for level in project.levels.iter() {
+        let mut report = |message: String| {
+            issues.push(Issue {
+                level: level.identifier.clone(),
+                message,
+            })
+        };
+        let players = level_entities(level)
+            .filter(|entity| entity.identifier == "Player")
+            .count();
+        if players != 1 {
+            report(format!("expected exactly one Player, found {}", players));
+        }
+        for entity in level_entities(level) {
+            if let Err(error) = validate_entity(entity) {
+                report(error.to_string());
+                continue;
+            }
+            if entity.identifier == "Box" {
+                if let Ok(Some(box_type)) = Fields::new(entity).optional_string("BoxImage") {
+                    if box_image_tile(project, &box_type).is_none() {
+                        report(format!(
+                            "Box entity {}: BoxImage {} has no BoxType icon",
+                            entity.iid, box_type
+                        ));
+                    }
+                }
+                continue;
+            }
+            if entity.identifier != "Door" {
+                continue;
+            }
+            if let Ok(next_level) = Fields::new(entity).string("next_level") {
+                if !level_iids.contains(next_level.as_str()) {
+                    report(format!(
+                        "Door entity {}: next level {} does not exist",
+                        entity.iid, next_level
+                    ));
+                }
+            }
+        }
+    }
+    issues
+}